@@ -0,0 +1,80 @@
+use crate::burgs::mazeburg::{self, square, Square, Walk};
+use crate::genes::*;
+use crate::geography::*;
+use crate::utils::*;
+use clap::*;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+//Same genome length and crowding as Mazeburg, so the walks hit memory the way its seekers do
+const NORMAL_STEPS: usize = 10;
+const OCCUPIED: f64 = 0.3;
+//Occupied squares belong to one of this many species
+const SPECIES: usize = 8;
+
+#[derive(Args, Debug, Copy, Clone)]
+pub struct BenchArgs {
+    #[arg(long, short, default_value_t = 1024)]
+    pub size: usize,
+    #[arg(long, default_value_t = 1_000)]
+    pub walkers: usize,
+    //Longest any one walk can go on, like Mazeburg's max age
+    #[arg(long, default_value_t = 2_000)]
+    pub steps: usize,
+    #[arg(long, value_enum, default_value_t = GridKind::Rw)]
+    pub grid: GridKind,
+    #[command(flatten)]
+    pub partition_args: PartitionArgs,
+}
+
+//Times the same number of Mazeburg seeks, with no maze, over every layout. Single threaded, so it's the memory being
+//measured and not the locks.
+pub fn run(args: BenchArgs) {
+    let partition = args.partition_args.partition(args.size, args.size, 1);
+    println!(
+        "{} walkers of up to {} steps on a {} grid of {}x{} ({} regions)",
+        args.walkers, args.steps, args.grid, args.size, args.size, partition
+    );
+    for layout in Layout::value_variants() {
+        let (elapsed, steps) = match args.grid {
+            GridKind::Wrapped => time::<LockedGrid<WrappedGrid<Square>>>(args, partition, *layout),
+            GridKind::Rw => time::<RwGrid<Square>>(args, partition, *layout),
+            GridKind::Regional => time::<RegionalGrid<Square>>(args, partition, *layout),
+            GridKind::Atomic => time::<AtomicGrid<Square>>(args, partition, *layout),
+        };
+        let per_step = elapsed.as_nanos() as f64 / steps as f64;
+        println!(
+            "{:>10}: {:>8.1?} total, {:>9} steps, {:>6.1} ns/step",
+            layout, elapsed, steps, per_step
+        );
+    }
+}
+
+fn time<G>(args: BenchArgs, partition: Partition, layout: Layout) -> (Duration, usize)
+where
+    G: ThreadedGrid<Square> + BuildGrid<Square>,
+{
+    let grid = G::build_with(args.size, args.size, square::EMPTY, partition, layout);
+    for p in grid.bounds().points() {
+        if roll::under(OCCUPIED) {
+            let species = roll::usize(SPECIES);
+            grid.set(p, Square { species });
+        }
+    }
+    let turns = MaskTable::new(mazeburg::turn);
+    let walk = Walk {
+        grid: &grid,
+        is_wall: |_| false,
+        turns: &turns,
+        max_age: args.steps,
+    };
+    let mut steps = 0;
+    let start = Instant::now();
+    for i in 0..args.walkers {
+        let genes = GenoType::new(NORMAL_STEPS, format!("bench:{i}"));
+        let (origin, species) = (grid.rand(), roll::usize(SPECIES));
+        let (lifetime, _, _) = black_box(walk.seek(species, origin, false, &genes));
+        steps += lifetime;
+    }
+    (start.elapsed(), steps)
+}
@@ -1,7 +1,9 @@
+use crate::geography::{GridKind, Layout, PartitionArgs};
 use crate::maze::*;
 use clap::*;
+use std::path::PathBuf;
 
-#[derive(Args, Debug, Copy, Clone)]
+#[derive(Args, Debug, Clone)]
 pub struct FoodburgArgs {
     #[arg(long, short, default_value_t = 1024)]
     pub size: usize,
@@ -11,6 +13,16 @@ pub struct FoodburgArgs {
     pub num_species: usize,
     #[arg(long, default_value_t = false)]
     pub wrapped: bool,
+    #[arg(long, value_enum, default_value_t = GridKind::Rw)]
+    pub grid: GridKind,
+    #[arg(long, value_enum, default_value_t = Layout::RowMajor)]
+    pub layout: Layout,
+    #[command(flatten)]
+    pub partition_args: PartitionArgs,
     #[command(flatten)]
     pub maze_args: MazeArgs,
+    //A world to start from instead of a maze, which sets the size. Either a PNG where black is empty, white is wall and
+    //green is food, or text where they're '.', '#' and '*'.
+    #[arg(long)]
+    pub world: Option<PathBuf>,
 }
@@ -30,7 +30,7 @@ static MOLD_COLORS: [Color; 11] = [
 ];
 
 #[derive(Copy, Clone, PartialEq, Hash, Eq, Debug)]
-enum Square {
+pub enum Square {
     Empty,
     Wall,
     Food,
@@ -40,6 +40,34 @@ enum Square {
         spawn_time: usize,
     },
 }
+//How --world images are read. Molds can't be painted in; species still start wherever there's room.
+static WORLD_PALETTE: [(Color, Square); 3] = [
+    (color::BLACK, Square::Empty),
+    (color::WHITE, Square::Wall),
+    (color::GREEN, Square::Food),
+];
+
+//The same for worlds written out as text
+fn world_legend() -> Legend<Square> {
+    Legend::new(Square::Empty)
+        .with('.', Square::Empty)
+        .with('#', Square::Wall)
+        .with('*', Square::Food)
+}
+
+impl Square {
+    //Which of the overview's colors the square counts towards: empty, wall, food, then one for each species
+    fn category(self) -> usize {
+        use self::Square::*;
+        match self {
+            Empty => 0,
+            Wall => 1,
+            Food => 2,
+            Mold { s, .. } => 3 + s,
+        }
+    }
+}
+
 impl Colored for Square {
     fn color(&self) -> Color {
         use self::Square::*;
@@ -59,6 +87,8 @@ enum GrowResult {
         s: SpeciesID,
         lifetime: usize,
         parent_dir: Compass,
+        //What it found to eat, still there until the growth commits
+        food: Point,
     },
     Aged {
         lifetime: usize,
@@ -66,16 +96,22 @@ enum GrowResult {
     SpawnDied,
 }
 
-pub struct Foodburg {
+pub struct Foodburg<G = RwGrid<Square>> {
     num_threads: usize,
     size: usize,
-    grid: RwGrid<Square>,
+    //Indexes the empty squares, for placing food and founding species, under an overview for drawing and census
+    grid: SampledGrid<OverviewGrid<G, Square>, Square>,
     species: Vec<Species>,
     actors: Mutex<BinaryHeap<Actor>>,
     draw_path: Mutex<Option<SpeciesID>>,
     path: Mutex<Option<Vec<(Point, Color)>>>,
+    //For the UI to compare against
+    checkpoint: Mutex<Option<WrappedGrid<Square>>>,
 }
-impl Petersburg for Foodburg {
+impl<G> Petersburg for Foodburg<G>
+where
+    G: ThreadedGrid<Square> + BuildGrid<Square> + Draw + Send + Sync + 'static,
+{
     fn run(&self) {
         crossbeam::scope(|scope| {
             for i in 0..self.num_threads {
@@ -106,11 +142,44 @@ impl Petersburg for Foodburg {
             }
             ()
         });
+        if self.grid.instrumented() {
+            draw_utils::heatmap_helper(context, self.size, &self.grid.lock_stats());
+        }
     }
 }
-impl Foodburg {
-    pub fn new(args: FoodburgArgs) -> Self {
-        let grid = Self::grid_init(args.size, args.maze_args, args.wrapped);
+impl<G> Foodburg<G>
+where
+    G: ThreadedGrid<Square> + BuildGrid<Square> + Draw + Send + Sync + 'static,
+{
+    pub fn new(mut args: FoodburgArgs) -> Self {
+        let world = args.world.as_ref().map(|path| {
+            let world = match path.extension().and_then(|e| e.to_str()) {
+                Some("png") => image::load(path, &WORLD_PALETTE),
+                _ => ascii::load(path, &world_legend()).map(|scene| scene.cells),
+            }
+            .unwrap_or_else(|e| panic!("Couldn't load {}: {e}", path.display()));
+            let Rect { width, height, .. } = world.bounds();
+            if width != height {
+                panic!(
+                    "Worlds have to be square, {} is {width}x{height}",
+                    path.display()
+                );
+            }
+            world
+        });
+        if let Some(world) = &world {
+            args.size = world.bounds().width;
+        }
+        let partition = args
+            .partition_args
+            .partition(args.size, args.size, args.num_threads);
+        let grid = Self::grid_init(&args, partition, world);
+        let colors = [color::BLACK, color::WHITE, color::GREEN]
+            .into_iter()
+            .chain(MOLD_COLORS.iter().copied().take(args.num_species))
+            .collect();
+        let grid = OverviewGrid::new(grid, colors, Square::category);
+        let grid = SampledGrid::new(grid, |s| s == Square::Empty);
         let species = Self::species_init(args.num_species, &grid);
         let actors = Self::actors_init(&species);
         Self {
@@ -121,15 +190,30 @@ impl Foodburg {
             actors,
             draw_path: Mutex::new(None),
             path: Mutex::new(None),
+            checkpoint: Mutex::new(None),
         }
     }
-    fn grid_init(size: usize, maze_args: MazeArgs, wrapped: bool) -> RwGrid<Square> {
-        let grid = RwGrid::<Square>::new(size, size, Square::Empty);
-        let maze = Maze::new(size, wrapped, maze_args);
-        for i in 0..size {
-            for j in 0..size {
-                let p = Point(i, j);
-                grid.set_if(p, |_| maze.is_wall(p), Square::Wall);
+    fn grid_init(
+        args: &FoodburgArgs,
+        partition: Partition,
+        world: Option<WrappedGrid<Square>>,
+    ) -> G {
+        let (size, wrapped) = (args.size, args.wrapped);
+        let grid = G::build_with(size, size, Square::Empty, partition, args.layout);
+        match world {
+            Some(world) => {
+                for (p, square) in world.iter() {
+                    grid.set(p, square);
+                }
+            }
+            None => {
+                let maze = Maze::new(size, wrapped, args.maze_args);
+                for i in 0..size {
+                    for j in 0..size {
+                        let p = Point(i, j);
+                        grid.set_if(p, |_| maze.is_wall(p), Square::Wall);
+                    }
+                }
             }
         }
         if !wrapped {
@@ -142,7 +226,10 @@ impl Foodburg {
         }
         grid
     }
-    fn species_init(num_species: usize, grid: &RwGrid<Square>) -> Vec<Species> {
+    fn species_init(
+        num_species: usize,
+        grid: &SampledGrid<OverviewGrid<G, Square>, Square>,
+    ) -> Vec<Species> {
         if num_species > MOLD_COLORS.len() {
             panic!("More colors required for that many species")
         }
@@ -150,7 +237,9 @@ impl Foodburg {
             .map(|s| {
                 let mut p;
                 'find_start: loop {
-                    p = grid.rand();
+                    p = grid
+                        .sample()
+                        .expect("No empty squares left to start a species on");
                     if grid.set_if(
                         p,
                         |s| s == Square::Empty,
@@ -245,6 +334,7 @@ impl Foodburg {
                             s,
                             lifetime,
                             parent_dir,
+                            food,
                         } => {
                             let time = time + lifetime;
                             let potential_square = Square::Mold {
@@ -252,11 +342,23 @@ impl Foodburg {
                                 parent_dir: Some(parent_dir),
                                 spawn_time: time,
                             };
-                            if self.grid.set_if(
-                                p,
-                                |square| square == Square::Empty,
-                                potential_square,
-                            ) {
+                            //Only grow if the square's still free, still next to the mold that found it, and the
+                            //food that paid for it hasn't been eaten by someone else in the meantime
+                            let parent = self.grid.step(p, parent_dir);
+                            let grown =
+                                self.grid.transact(&[p, parent, food], |cells| match cells {
+                                    [square, Square::Mold { s: parent_s, .. }, eaten]
+                                        if *square == Square::Empty
+                                            && *parent_s == s
+                                            && *eaten == Square::Food =>
+                                    {
+                                        *square = potential_square;
+                                        *eaten = Square::Empty;
+                                        true
+                                    }
+                                    _ => false,
+                                });
+                            if grown {
                                 if *queued_count < MAX_LIVING {
                                     for _ in 0..CHILD_COUNT {
                                         actors.push(SporeSpawn { s, p, time });
@@ -265,8 +367,6 @@ impl Foodburg {
                                     drop(actors);
                                 }
                             } else {
-                                println!("{} from {spawn_p}, landed on {p}. Attempted growth on non-empty square after {lifetime} steps. Oh well.", self.species[s]);
-                                println!("(BTW, the queue count is {queued_count} and this is thread {thread_id}");
                                 actors.push(SporeSpawn {
                                     s,
                                     p: self.species[s].root,
@@ -312,7 +412,7 @@ impl Foodburg {
                 return StepResult::Die;
             }
             if n.c == Square::Food {
-                return StepResult::Stick(Square::Empty);
+                return StepResult::Stick(Square::Food);
             }
             StepResult::Step(Some(dir))
         };
@@ -322,6 +422,7 @@ impl Foodburg {
                 s,
                 lifetime,
                 parent_dir: dir,
+                food: p,
             },
             PathResult::Died(_) => GrowResult::Aged { lifetime },
         }
@@ -341,7 +442,7 @@ impl Foodburg {
 
         let mut dir = Compass::rand();
         let mut lifetime = 0;
-        'seek_food: loop {
+        let food = loop {
             if draw_path {
                 path.push((p, self.species[s].color))
             }
@@ -359,20 +460,14 @@ impl Foodburg {
                 }
                 return GrowResult::Aged { lifetime };
             };
-            let neighbors = Compass::all()
-                .into_iter()
-                .map(|n_dir| self.grid.step(p, n_dir));
-            for neighbor in neighbors {
-                if self
-                    .grid
-                    .set_if(neighbor, |s| s == Square::Food, Square::Empty)
-                {
-                    break 'seek_food;
-                }
+            //Nothing's eaten yet - that waits until the mold grows
+            let food = self.grid.mask(p, |s| s == Square::Food);
+            if let Some(n_dir) = Compass::all().into_iter().find(|d| food.contains(*d)) {
+                break self.grid.step(p, n_dir);
             }
             Self::update_dir(&mut dir);
             self.bounce_move(&mut p, &mut dir);
-        }
+        };
         loop {
             if draw_path {
                 path.push((p, self.species[s].color))
@@ -391,32 +486,27 @@ impl Foodburg {
                 }
                 return GrowResult::Aged { lifetime };
             };
-            for facing_dir in Compass::all().into_iter() {
-                match self.grid.get(self.grid.step(p, facing_dir)) {
-                    Square::Mold { s: neighbor_s, .. } if neighbor_s == s => {
-                        return GrowResult::Success {
-                            p,
-                            s,
-                            lifetime,
-                            parent_dir: facing_dir,
-                        };
-                    }
-                    _ => (),
-                }
+            let kin = self.grid.mask(
+                p,
+                |sq| matches!(sq, Square::Mold { s: neighbor_s, .. } if neighbor_s == s),
+            );
+            if let Some(parent_dir) = Compass::all().into_iter().find(|d| kin.contains(*d)) {
+                return GrowResult::Success {
+                    p,
+                    s,
+                    lifetime,
+                    parent_dir,
+                    food,
+                };
             }
             Self::update_dir(&mut dir);
             self.bounce_move(&mut p, &mut dir);
         }
-
     }
     fn grow_food(&self) {
-        let mut p;
-        'find_start: loop {
-            p = self.grid.rand();
-            if self.grid.get(p) == Square::Empty {
-                break 'find_start;
-            }
-        }
+        let Some(mut p) = self.grid.sample() else {
+            return;
+        };
         let mut dir = Compass::rand();
         let mut ripeness = 0;
         'ripen: loop {
@@ -524,6 +614,32 @@ impl Foodburg {
                         elem.active_count.lock().unwrap()
                     );
                 }
+            } else if line.starts_with("census") {
+                let overview = self.grid.inner();
+                println!("Food: {}", overview.total(Square::Food.category()));
+                for (s, elem) in self.species.iter().enumerate() {
+                    let mold = Square::Mold {
+                        parent_dir: None,
+                        s,
+                        spawn_time: 0,
+                    };
+                    println!("{elem}: {} squares", overview.total(mold.category()));
+                }
+            } else if line.starts_with("fragments") {
+                let components =
+                    components::label(&self.grid, &Compass::all(), |square| match square {
+                        Square::Mold { s, .. } => Some(s),
+                        _ => None,
+                    });
+                for (s, elem) in self.species.iter().enumerate() {
+                    let sizes: Vec<usize> = components.of(s).map(|c| c.size).collect();
+                    let perimeter: usize = components.of(s).map(|c| c.perimeter).sum();
+                    println!(
+                        "{elem}: {} pieces, the biggest {} squares, {perimeter} edge in all",
+                        sizes.len(),
+                        sizes.iter().max().unwrap_or(&0)
+                    );
+                }
             } else if line.starts_with("queue") {
                 use self::Actor::*;
                 let actors = self.actors.lock().unwrap();
@@ -537,6 +653,46 @@ impl Foodburg {
                         }
                     }
                 }
+            } else if let Some(path) = line.strip_prefix("save ") {
+                let path = path.trim();
+                //Molds don't survive saving, so the file can be loaded straight back in as a --world
+                let world = self.grid.map(|s| match s {
+                    Square::Mold { .. } => Square::Empty,
+                    s => s,
+                });
+                let saved = if path.ends_with(".png") {
+                    image::save(&world, path)
+                } else {
+                    let text = ascii::to_ascii(&world, &world_legend());
+                    std::fs::write(path, text).map_err(|e| e.to_string())
+                };
+                match saved {
+                    Ok(()) => println!("Saved to {}", path),
+                    Err(e) => println!("Couldn't save to {}: {e}", path),
+                }
+            } else if line.starts_with("checkpoint") {
+                *self.checkpoint.lock().unwrap() = Some(self.grid.snapshot());
+            } else if line.starts_with("changes") {
+                match self.checkpoint.lock().unwrap().as_ref() {
+                    Some(checkpoint) => {
+                        let changes = feed::diff(checkpoint, &self.grid).unwrap();
+                        let grown = changes
+                            .iter()
+                            .filter(|c| matches!(c.new, Square::Mold { .. }))
+                            .count();
+                        println!(
+                            "{} squares changed since the checkpoint, {grown} of them to mold",
+                            changes.len()
+                        );
+                    }
+                    None => println!("No checkpoint to compare to"),
+                }
+            } else if line.starts_with("contention on") {
+                self.grid.set_instrumented(true);
+            } else if line.starts_with("contention off") {
+                self.grid.set_instrumented(false);
+            } else if line.starts_with("contention") {
+                contention::report(&self.grid.lock_stats());
             } else if line.starts_with("paths off") {
                 let mut path_species = self.draw_path.lock().unwrap();
                 *path_species = None;
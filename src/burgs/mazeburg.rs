@@ -1,6 +1,8 @@
 mod mazeburg;
-pub(self) mod square;
+pub(crate) mod square;
 pub use self::mazeburg::types::*;
-pub(self) use self::square::Square;
+pub(crate) use self::mazeburg::Walk;
+pub use self::mazeburg::turn;
+pub(crate) use self::square::Square;
 mod args;
 pub use self::args::MazeburgArgs;
\ No newline at end of file
@@ -1,3 +1,4 @@
+use crate::geography::{GridKind, Layout, Metric, PartitionArgs};
 use crate::maze::*;
 use clap::*;
 
@@ -13,6 +14,14 @@ pub struct MazeburgArgs {
     pub wrapped: bool,
     #[arg(long, default_value_t = false)]
     pub show_lines: bool,
+    #[arg(long, value_enum, default_value_t = Metric::Euclidean)]
+    pub metric: Metric,
+    #[arg(long, value_enum, default_value_t = GridKind::Rw)]
+    pub grid: GridKind,
+    #[arg(long, value_enum, default_value_t = Layout::RowMajor)]
+    pub layout: Layout,
+    #[command(flatten)]
+    pub partition_args: PartitionArgs,
     #[command(flatten)]
     pub maze_args: MazeArgs,
 }
@@ -6,10 +6,9 @@ use crate::genes::*;
 use crate::maze::*;
 use cairo::Context;
 use std::fmt;
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex};
 
 type MoveDir = Compass;
-type BuildDir = Compass;
 const MAX_CHILDREN: usize = 200;
 //const MAX_AGE: i64 = SIZE as i64 * 200;
 const TURNINESS: usize = 32;
@@ -24,6 +23,99 @@ pub mod types {
     pub use super::Mazeburg;
 }
 
+//How a seeker turns when it runs into something: eighth turns to the right, given which squares are blocked as seen
+//facing north. None means carry on.
+pub fn turn(blocked: Mask) -> Option<usize> {
+    use Compass::*;
+    if !(blocked.contains(N) || blocked.contains(NW) && blocked.contains(NE)) {
+        None
+    } else if blocked.contains(W) {
+        Some(2)
+    } else if blocked.contains(E) {
+        Some(6)
+    } else {
+        Some(4)
+    }
+}
+
+//What a seeker walks through. The bench runs the same walk with no maze, so walls are just a function.
+pub(crate) struct Walk<'a, G, W> {
+    pub grid: &'a G,
+    pub is_wall: W,
+    pub turns: &'a MaskTable<Option<usize>>,
+    pub max_age: usize,
+}
+
+impl<G: Grid<Square>, W: Fn(Point) -> bool> Walk<'_, G, W> {
+    //Follow the genes until sticking next to kin and nothing else, getting too old or running out of genes. Runs into
+    //walls and other seekers turn by the table.
+    pub fn seek(
+        &self,
+        index: usize,
+        origin: Point,
+        track_path: bool,
+        steps: &GenoType,
+    ) -> (usize, Result, Vec<Point>) {
+        let mut p = origin;
+        let mut steps = steps.clone();
+        let mut path = Vec::new();
+        let mut lifetime = 0;
+        let mut time_to_next: i32 = 0;
+        let mut dir = Compass::N;
+        'seek: loop {
+            if track_path {
+                path.push(p);
+            }
+            lifetime = lifetime + 1;
+            time_to_next = time_to_next - roll::i32(0, 3);
+            if lifetime >= self.max_age {
+                return (lifetime, Result::Aged, path);
+            }
+            if time_to_next <= 0 {
+                match steps.0.split_first() {
+                    None => {
+                        return (lifetime, Result::Aged, path);
+                    }
+                    Some((step, rest)) => {
+                        let rest = rest.to_owned();
+                        dir = step.dir;
+                        time_to_next = step.time_to_next as i32;
+                        steps = GenoType(rest.to_vec(), "".to_string());
+                    }
+                }
+            }
+            {
+                let grid = self.grid;
+                let n = grid.get_neighborhood(p);
+                let kin = Mask::of(&n, |sq| sq.species == index);
+                let occupied = Mask::of(&n, |sq| sq != square::EMPTY);
+                let walls = Mask::from_fn(|d| (self.is_wall)(grid.step(p, d)));
+                //Stick?
+                if !kin.is_empty() && (occupied & !kin).is_empty() {
+                    return (lifetime, Result::Stuck(p), path);
+                };
+                //Turn?
+                if let Some(eighths) = self.turns[(walls | occupied).facing(dir)] {
+                    dir = Compass::from(dir as usize + eighths)
+                } else {
+                    if walls.is_empty() && occupied.is_empty() {
+                        let roll = roll::usize(TURNINESS);
+                        if roll == 0 {
+                            p = grid.step(p, dir.right())
+                        } else if roll == 1 {
+                            p = grid.step(p, dir.left())
+                        } else {
+                            p = grid.step(p, dir);
+                        }
+                    } else {
+                        p = grid.step(p, dir);
+                    }
+                }
+            }
+        }
+    }
+}
+
 struct Species {
     index: usize,
     origin: Point,
@@ -33,6 +125,7 @@ struct Species {
     dead_streak: usize,
     root_ancestors: usize,
     fitness: f64,
+    difficulty: Option<f64>,
     candidates: Vec<GenoType>,
 }
 
@@ -48,23 +141,30 @@ impl fmt::Display for Species {
     }
 }
 #[allow(dead_code)]
-enum Result {
+pub(crate) enum Result {
     Stuck(Point),
     Aged,
     Crashed,
 }
 
-pub struct Mazeburg {
+pub struct Mazeburg<G = RwGrid<Square>> {
     args: MazeburgArgs,
     maze: Arc<Maze>,
-    grid_rw: Arc<RwLock<RwGrid<Square>>>,
+    grid: G,
     draw_path_m: Arc<Mutex<(Color, Vec<Point>)>>,
     species_m: Arc<Mutex<Vec<Species>>>,
     draw_line_params: Arc<Mutex<(bool, Option<usize>)>>,
     max_age: usize,
+    zones: Zones,
+    show_zones: Mutex<bool>,
+    //turn, looked up rather than worked out every step
+    turns: MaskTable<Option<usize>>,
 }
 
-impl Petersburg for Mazeburg {
+impl<G> Petersburg for Mazeburg<G>
+where
+    G: ThreadedGrid<Square> + BuildGrid<Square> + Draw + Send + Sync + 'static,
+{
     fn run(&self) {
         crossbeam::scope(|scope| {
             for i in 0..self.args.num_threads {
@@ -81,7 +181,13 @@ impl Petersburg for Mazeburg {
     fn draw(&self, context: &Context) {
         self.maze.draw(context);
         let size = self.args.size;
-        self.grid_rw.read().unwrap().draw(context);
+        self.grid.draw(context);
+        if *self.show_zones.lock().unwrap() {
+            self.zones.draw(context);
+        }
+        if self.grid.instrumented() {
+            draw_utils::heatmap_helper(context, size, &self.grid.lock_stats());
+        }
         let path_params = self.draw_line_params.lock().unwrap();
         let draw_path = path_params.0;
         drop(path_params);
@@ -92,10 +198,16 @@ impl Petersburg for Mazeburg {
     }
 }
 
-impl Mazeburg {
+impl<G> Mazeburg<G>
+where
+    G: ThreadedGrid<Square> + BuildGrid<Square> + Draw + Send + Sync + 'static,
+{
     pub fn new(args: MazeburgArgs) -> Self {
         let maze_raw = Maze::new(args.size, args.wrapped, args.maze_args);
-        let mut grid: RwGrid<Square> = RwGrid::new(args.size, args.size, square::EMPTY);
+        let partition = args
+            .partition_args
+            .partition(args.size, args.size, args.num_threads);
+        let grid = G::build_with(args.size, args.size, square::EMPTY, partition, args.layout);
         let min_distance = (args.size as f64
             * if args.wrapped {
                 MIN_DISTANCE_WRAPPED
@@ -103,22 +215,25 @@ impl Mazeburg {
                 MIN_DISTANCE_UNWRAPPED
             }) as usize;
         let line_params_m = Arc::new(Mutex::new((args.show_lines, None)));
+        let zones = Self::zones_init(args.size, &grid);
         let species = (0..args.num_species)
-            .map(|i| Self::species_init(args, i, &mut grid, &maze_raw, min_distance))
+            .map(|i| Self::species_init(args, i, &grid, &maze_raw, &zones, min_distance))
             .collect::<Vec<Species>>();
         let species_m = Arc::new(Mutex::new(species));
-        let grid_rw = Arc::new(RwLock::new(grid));
         let maze = Arc::new(maze_raw);
         let draw_path_m = Arc::new(Mutex::new((color::BLACK, Vec::new())));
         let max_age = args.size * 200;
         Self {
             args,
             maze,
-            grid_rw,
+            grid,
             draw_path_m,
             species_m,
             draw_line_params: line_params_m,
             max_age,
+            zones,
+            show_zones: Mutex::new(false),
+            turns: MaskTable::new(turn),
         }
     }
     fn run_thread(&self, _thread_index: usize) {
@@ -192,47 +307,58 @@ impl Mazeburg {
                             }
                             this_species.dead_streak = 0;
                             drop(species);
-                            let mut grid = self.grid_rw.write().unwrap();
-                            grid.set(p, Square { species: index });
-                            drop(grid);
+                            self.grid.set(p, Square { species: index });
                         }
                     }
                 }
             };
         }
     }
+    //Destinations are somewhere along an edge, origins somewhere in the middle
+    fn zones_init(size: usize, grid: &G) -> Zones {
+        let everything = Rect::new(Point(0, 0), size, size);
+        let middle = Rect::new(Point(3 * size / 8, 3 * size / 8), size / 4, size / 4);
+        Zones::new()
+            .with(
+                "destinations",
+                Zone::band(grid, everything, size / 8).with_color(color::YELLOW),
+            )
+            .with("origins", Zone::rect(grid, middle).with_color(color::TEAL))
+    }
     fn species_init(
         args: MazeburgArgs,
         index: usize,
-        grid: &mut RwGrid<Square>,
+        grid: &G,
         maze: &Maze,
+        zones: &Zones,
         min_distance: usize,
     ) -> Species {
         let mut destination;
         let mut origin;
-        let (size, wrapped) = (args.size, args.wrapped);
+        let mut distance;
+        let wrapped = args.wrapped;
         loop {
-            destination = match roll::usize(4) {
-                0 => Point(roll::usize(size), roll::usize(size / 8) + 7 * size / 8),
-                1 => Point(roll::usize(size / 8) + 7 * size / 8, roll::usize(size)),
-                2 => Point(roll::usize(size), roll::usize(size / 8)),
-                _ => Point(roll::usize(size / 8), roll::usize(size)),
+            (destination, origin) = match (
+                zones["destinations"].sample_where(|p| !maze.is_wall(p)),
+                zones["origins"].sample_where(|p| !maze.is_wall(p)),
+            ) {
+                (Some(destination), Some(origin)) => (destination, origin),
+                _ => continue,
             };
-            origin = Point(
-                roll::usize(size / 4) + 3 * size / 8,
-                roll::usize(size / 4) + 3 * size / 8,
-            );
-            if !maze.is_wall(origin)
-                && !maze.is_wall(destination)
-                && if wrapped {
-                    grid.distance(origin, destination)
-                } else {
-                    Point::distance(origin, destination)
-                } > min_distance as f64
-            {
+            distance = if wrapped {
+                grid.metric_distance(origin, destination, args.metric)
+            } else {
+                args.metric.flat(origin, destination)
+            };
+            if distance > min_distance as f64 {
                 break;
             };
         }
+        //How much further the maze makes you walk than the crow flies
+        let difficulty = pathing::bfs(grid, origin, destination, &MoveDir::all(), |p, _| {
+            !maze.is_wall(p)
+        })
+        .map(|path| path.cost / distance);
         grid.set(destination, Square { species: index });
         let color = color::COLORS[index];
         Species {
@@ -244,6 +370,7 @@ impl Mazeburg {
             dead_streak: 0,
             root_ancestors: 0,
             fitness: 0.0,
+            difficulty,
             candidates: Vec::new(),
         }
     }
@@ -255,82 +382,13 @@ impl Mazeburg {
         track_path: bool,
         steps: &GenoType,
     ) -> (usize, Result, Vec<Point>) {
-        let mut p = origin;
-        let mut steps = steps.clone();
-        let mut path = Vec::new();
-        let mut lifetime = 0;
-        let mut time_to_next: i32 = 0;
-        let mut dir = Compass::N;
-        'seek: loop {
-            if track_path {
-                path.push(p);
-            }
-            lifetime = lifetime + 1;
-            time_to_next = time_to_next - roll::i32(0, 3);
-            if lifetime >= self.max_age {
-                return (lifetime, Result::Aged, path);
-            }
-            if time_to_next <= 0 {
-                match steps.0.split_first() {
-                    None => {
-                        return (lifetime, Result::Aged, path);
-                    }
-                    Some((step, rest)) => {
-                        let rest = rest.to_owned();
-                        dir = step.dir;
-                        time_to_next = step.time_to_next as i32;
-                        steps = GenoType(rest.to_vec(), "".to_string());
-                    }
-                }
-            }
-            let mut open = true;
-            {
-                //Critical block
-                let grid = self.grid_rw.read().unwrap();
-                //Stick?
-                let (mut friendly, mut unfriendly) = (false, false);
-                for neighbor_dir in BuildDir::all() {
-                    if grid.get(grid.step(p, neighbor_dir)).species == index {
-                        friendly = true;
-                    } else if grid.get(grid.step(p, neighbor_dir)) != square::EMPTY {
-                        unfriendly = true;
-                    }
-                    if self.maze.is_wall(grid.step(p, neighbor_dir)) {
-                        open = false
-                    }
-                }
-                if friendly && !unfriendly {
-                    return (lifetime, Result::Stuck(p), path);
-                };
-                //Turn?
-                let blocked = |dir| {
-                    let next = grid.step(p, dir);
-                    self.maze.is_wall(next) || grid.get(next) != square::EMPTY
-                };
-                if blocked(dir) || (blocked(dir.left()) && blocked(dir.right())) {
-                    if blocked(dir.left().left()) {
-                        dir = dir.right().right()
-                    } else if blocked(dir.right().right()) {
-                        dir = dir.left().left()
-                    } else {
-                        dir = dir.reverse()
-                    }
-                } else {
-                    if open && !friendly && !unfriendly {
-                        let roll = roll::usize(TURNINESS);
-                        if roll == 0 {
-                            p = grid.step(p, dir.right())
-                        } else if roll == 1 {
-                            p = grid.step(p, dir.left())
-                        } else {
-                            p = grid.step(p, dir);
-                        }
-                    } else {
-                        p = grid.step(p, dir);
-                    }
-                }
-            }
-        }
+        let walk = Walk {
+            grid: &self.grid,
+            is_wall: |p| self.maze.is_wall(p),
+            turns: &self.turns,
+            max_age: self.max_age,
+        };
+        walk.seek(index, origin, track_path, steps)
     }
 
     fn stdin_io(&self) {
@@ -346,14 +404,45 @@ impl Mazeburg {
                         \t {} root_ancestors
                         \t {} candidates
                         \t {:.5} fitness
-                        \t {} deadiness",
+                        \t {} deadiness
+                        \t {:.2?} difficulty",
                         elem.alive,
                         elem.root_ancestors,
                         elem.candidates.len(),
                         elem.fitness,
-                        elem.dead_streak
+                        elem.dead_streak,
+                        elem.difficulty
                     );
                 }
+            } else if line.starts_with("contention on") {
+                self.grid.set_instrumented(true);
+            } else if line.starts_with("contention off") {
+                self.grid.set_instrumented(false);
+            } else if line.starts_with("contention") {
+                contention::report(&self.grid.lock_stats());
+            } else if line.starts_with("clusters") {
+                let components = components::label(&self.grid, &Compass::all(), |square| {
+                    (square != square::EMPTY).then_some(square.species)
+                });
+                let species = self.species_m.lock().unwrap();
+                for elem in species.iter() {
+                    let clusters: Vec<&Cluster<usize>> = components.of(elem.index).collect();
+                    let biggest = clusters.iter().max_by_key(|c| c.size);
+                    match biggest {
+                        Some(biggest) => println!(
+                            "{elem}: {} clusters, the biggest {} squares across {}x{}",
+                            clusters.len(),
+                            biggest.size,
+                            biggest.bounds.width,
+                            biggest.bounds.height
+                        ),
+                        None => println!("{elem}: nothing built yet"),
+                    }
+                }
+            } else if line.starts_with("zones on") {
+                *self.show_zones.lock().unwrap() = true;
+            } else if line.starts_with("zones off") {
+                *self.show_zones.lock().unwrap() = false;
             } else if line.starts_with("paths on") {
                 self.draw_line_params.lock().unwrap().0 = true;
             } else if line.starts_with("paths off") {
@@ -390,18 +479,11 @@ impl Mazeburg {
                 return (lifetime, Result::Aged, path);
             }
             {
-                //Critical block
-                let grid = self.grid_rw.read().unwrap();
+                let grid = &self.grid;
 
-                let (mut friendly, mut unfriendly) = (false, false);
-                for neighbor_dir in BuildDir::all() {
-                    if grid.get(grid.step(p, neighbor_dir)).species == index {
-                        friendly = true;
-                    } else if grid.get(grid.step(p, neighbor_dir)) != square::EMPTY {
-                        unfriendly = true;
-                    }
-                }
-                if friendly && !unfriendly {
+                let n = grid.get_neighborhood(p);
+                let kin = Mask::of(&n, |sq| sq.species == index);
+                if !kin.is_empty() && Mask::of(&n, |sq| sq != square::EMPTY) == kin {
                     return (lifetime, Result::Stuck(p), path);
                 };
                 let next = grid.step(p, dir);
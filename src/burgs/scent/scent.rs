@@ -1,6 +1,5 @@
 use super::super::*;
 use clap::*;
-use std::sync::{Arc, Mutex};
 #[allow(unused_imports)]
 
 pub mod types {
@@ -8,73 +7,101 @@ pub mod types {
     pub use super::ScentburgArgs;
 }
 
-#[derive(Copy, Clone)]
-pub struct ScentSquare {
-    pub food: usize,
-    pub home: usize,
-    pub stuck: bool,
+//Whether a walker has come to rest here
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Stuck(pub bool);
+impl Colored for Stuck {
+    fn color(&self) -> Color {
+        if self.0 {
+            color::WHITE
+        } else {
+            color::BLACK
+        }
+    }
 }
-pub struct Scentburg {
+pub struct Scentburg<G = LockedGrid<WrappedGrid<()>>> {
     size: usize,
-    grid: Arc<Mutex<WrappedGrid<ScentSquare>>>,
-    draw_grid: Arc<Mutex<WrappedGrid<Color>>>,
+    grid: LayeredGrid<G>,
+    home: Layer<usize>,
+    stuck: Layer<Stuck>,
+    home_field: DistanceField,
 }
 
 #[derive(Args, Copy, Clone, Debug)]
 pub struct ScentburgArgs {
     #[arg(long, short, default_value_t = 1024)]
     pub size: usize,
+    #[arg(long, value_enum, default_value_t = GridKind::Wrapped)]
+    pub grid: GridKind,
+    #[arg(long, value_enum, default_value_t = Layout::RowMajor)]
+    pub layout: Layout,
 }
 
-impl Petersburg for Scentburg {
+impl<G> Petersburg for Scentburg<G>
+where
+    G: LayerKind<usize> + LayerKind<Stuck>,
+    <G as LayerKind<Stuck>>::Layer: Draw,
+{
     fn run(&self) {
         let mut successes = 0;
         let center = Point(self.size / 2, self.size / 2);
-        let mut grid = self.grid.lock().unwrap();
-        grid.update(center, |s| ScentSquare { stuck: true, ..s });
-        drop(grid);
+        let stuck = self.grid.layer(self.stuck);
+        stuck.set(center, Stuck(true));
         let mut time_step = 0;
         let mut start = center;
+        //(steps that really got closer to home, total steps) on the way back
+        let mut homing = (0, 0);
         loop {
             let start_time = time_step;
-            start = self.seek(start, &mut time_step);
+            start = self.seek(start, &mut time_step, &mut homing);
             successes = successes + 1;
-            let mut grid = self.grid.lock().unwrap();
-            grid.update(start, |s| ScentSquare { stuck: true, ..s });
-            drop(grid);
-            let mut draw_grid = self.draw_grid.lock().unwrap();
-            draw_grid.set(start, color::WHITE);
-            drop(draw_grid);
+            stuck.set(start, Stuck(true));
             //tx.send(new_point).unwrap();
             if time_step - start_time < 3 {
                 break;
             };
         }
         println!("All done with a total time_step of {}", time_step);
+        println!(
+            "Scent led closer to home on {} of {} steps",
+            homing.0, homing.1
+        );
     }
 
     fn draw(&self, context: &cairo::Context) {
-        self.draw_grid.lock().unwrap().draw(context);
+        self.grid.draw(context);
     }
 }
 
-impl Scentburg {
+impl<G> Scentburg<G>
+where
+    G: LayerKind<usize> + LayerKind<Stuck>,
+    <G as LayerKind<Stuck>>::Layer: Draw,
+{
     pub fn new(c: ScentburgArgs) -> Self {
-        let empty = ScentSquare {
-            food: 0,
-            home: 0,
-            stuck: false,
-        };
-        let grid: WrappedGrid<ScentSquare> = WrappedGrid::new(c.size, c.size, empty);
-        let draw_grid: WrappedGrid<Color> = WrappedGrid::new(c.size, c.size, color::BLACK);
+        let mut grid = LayeredGrid::build_with(c.size, c.size, Partition::default(), c.layout);
+        let home = grid.add("home", 0);
+        let stuck = grid.add_drawn("stuck", Stuck(false));
+        //Ground truth for what the home scent is trying to approximate
+        let center = Point(c.size / 2, c.size / 2);
+        let home_field = pathing::distance_field(
+            grid.topology(),
+            &[center],
+            &Compass::all(),
+            |_, _| Some(1.0),
+            None,
+        );
         Scentburg {
             size: c.size,
-            grid: Arc::new(Mutex::new(grid)),
-            draw_grid: Arc::new(Mutex::new(draw_grid)),
+            grid,
+            home,
+            stuck,
+            home_field,
         }
     }
-    fn seek(&self, start: Point, time_step: &mut usize) -> Point {
-        let mut grid = self.grid.lock().unwrap();
+    fn seek(&self, start: Point, time_step: &mut usize, homing: &mut (usize, usize)) -> Point {
+        let topology = self.grid.topology();
+        let home = self.grid.layer(self.home);
         let mut dir = Compass::rand();
         let mut p = start;
         let mut homesickness = self.size * self.size;
@@ -84,42 +111,35 @@ impl Scentburg {
             if p.0 == 0 || p.1 == 0 || p.0 == self.size - 1 || p.1 == self.size - 1 {
                 break 'seek_food;
             }
-            let ScentSquare { food, home, stuck } = grid.get(p);
             //let home_scent = if (homesickness as usize) > *time_step  {0} else {*time_step - (homesickness as usize)};
-            let home_scent = std::cmp::max(homesickness as usize, home);
-            grid.set(
-                p,
-                ScentSquare {
-                    food: food,
-                    home: home_scent,
-                    stuck: stuck,
-                },
-            );
+            home.update(p, |home_scent| {
+                std::cmp::max(homesickness as usize, home_scent)
+            });
             let drift = rand::random::<usize>() % 8;
             if drift == 0 {
                 dir = dir.left()
             } else if drift == 7 {
                 dir = dir.right()
             };
-            p = grid.step(p, dir);
+            p = topology.step(p, dir);
         }
         'seek_home: loop {
             *time_step = *time_step + 1;
-            let hood = grid.get_neighborhood(p);
+            let hood = self.grid.get_neighborhood(p, self.stuck);
             for neighbor in hood {
-                if neighbor.stuck {
+                if neighbor.0 {
                     break 'seek_home;
                 }
             }
 
-            let spos = grid.step(p, dir);
-            let rpos = grid.step(p, dir.right());
-            let lpos = grid.step(p, dir.left());
+            let spos = topology.step(p, dir);
+            let rpos = topology.step(p, dir.right());
+            let lpos = topology.step(p, dir.left());
             let (mut sweight, mut lweight, mut rweight) = (3, 1, 1);
 
-            let sh = grid.get(spos).home;
-            let rh = grid.get(rpos).home;
-            let lh = grid.get(lpos).home;
+            let sh = home.get(spos);
+            let rh = home.get(rpos);
+            let lh = home.get(lpos);
 
             if sh > lh {
                 sweight = sweight + 2
@@ -137,6 +157,7 @@ impl Scentburg {
                 rweight = rweight + 2
             }
             let roll = rand::random::<usize>() % (lweight + rweight + sweight);
+            let previous = p;
             if roll < sweight {
                 p = spos;
             } else if roll < sweight + rweight {
@@ -146,6 +167,10 @@ impl Scentburg {
                 dir = dir.left();
                 p = lpos
             }
+            if self.home_field.get(p) < self.home_field.get(previous) {
+                homing.0 += 1;
+            }
+            homing.1 += 1;
         }
         p
     }
@@ -1,4 +1,4 @@
-
+use crate::geography::{GridKind, Layout, Metric, PartitionArgs};
 use clap::*;
 
 #[derive(Args, Debug, Copy, Clone)]
@@ -7,4 +7,12 @@ pub struct SimpleArgs {
     pub size: usize,
     #[arg(long, default_value_t = 8)]
     pub num_threads: usize,
-}
\ No newline at end of file
+    #[arg(long, value_enum, default_value_t = Metric::Euclidean)]
+    pub metric: Metric,
+    #[arg(long, value_enum, default_value_t = GridKind::Wrapped)]
+    pub grid: GridKind,
+    #[arg(long, value_enum, default_value_t = Layout::RowMajor)]
+    pub layout: Layout,
+    #[command(flatten)]
+    pub partition_args: PartitionArgs,
+}
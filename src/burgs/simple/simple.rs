@@ -2,7 +2,7 @@ use cairo::Context;
 
 #[allow(unused_imports)]
 use super::{super::*, *};
-use std::sync::{Arc, Mutex, RwLock};
+use std::sync::{Arc, Mutex};
 
 const MAX_LIFE: usize = 10_000;
 
@@ -11,48 +11,77 @@ pub mod types {
 }
 //type DrawGrid = Vec<Vec<Color>>;
 
-pub struct Simpleburg {
+const EMPTY: Particle = Particle(usize::MAX);
+
+//Which thread stuck a particle here, so the grid can draw itself
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Particle(usize);
+impl Colored for Particle {
+    fn color(&self) -> Color {
+        if *self == EMPTY {
+            color::BLACK
+        } else {
+            color::COLORS[self.0]
+        }
+    }
+}
+
+pub struct Simpleburg<G = LockedGrid<WrappedGrid<Particle>>> {
     args: SimpleArgs,
-    draw_grid_m: Arc<Mutex<WrappedGrid<Color>>>,
     draw_path_m: Arc<Mutex<(Color, Vec<Point>)>>,
-    grid_rw: Arc<RwLock<WrappedGrid<usize>>>,
+    grid: G,
+    zones: Zones,
 }
-impl Simpleburg {
+impl<G> Simpleburg<G>
+where
+    G: ThreadedGrid<Particle> + BuildGrid<Particle> + Draw + Send + Sync + 'static,
+{
     pub fn new(args: SimpleArgs) -> Self {
         use self::color::*;
         let draw_path_m = Arc::new(Mutex::new((BLACK, Vec::<Point>::new())));
-        let draw_grid_m = Arc::new(Mutex::new(WrappedGrid::<Color>::new(
-            args.size,
-            args.size,
-            color::BLACK,
-        )));
-        let grid = WrappedGrid::<usize>::new(args.size, args.size, usize::MAX);
-        let grid_rw = Arc::new(RwLock::new(grid));
+        let partition = args
+            .partition_args
+            .partition(args.size, args.size, args.num_threads);
+        let grid = G::build_with(args.size, args.size, EMPTY, partition, args.layout);
+        let zones = Self::zones_init(&args, &grid);
         Self {
             args,
-            draw_grid_m,
             draw_path_m,
-            grid_rw,
+            grid,
+            zones,
         }
     }
-    fn seek_threaded(
-        &self,
-        g_mut: Arc<RwLock<WrappedGrid<usize>>>,
-        draw_grid_mut: Arc<Mutex<WrappedGrid<Color>>>,
-        draw_path_mut: Arc<Mutex<(Color, Vec<Point>)>>,
-        index: usize,
-    ) {
-        let size = self.args.size;
+    //Particles start in the seed, stick if they wander out of the arena, and the run's over once one sticks in the goal
+    fn zones_init(args: &SimpleArgs, grid: &G) -> Zones {
+        let (size, metric) = (args.size, args.metric);
         let center = Point(size / 2, size / 2);
-        let color = color::COLORS[index as usize];
-        let (mut total, mut lived, mut steps, mut aged, crashed) = (0, 0, 0, 0, 0);
+        let seed = Rect::new(
+            Point(size / 2 - size / 32, size / 2 - size / 32),
+            size / 16,
+            size / 16,
+        );
+        let arena = (size / 2 - 2) as f64;
+        Zones::new()
+            .with("seed", Zone::rect(grid, seed))
+            .with("arena", Zone::circle(grid, center, arena, metric))
+            .with(
+                "goal",
+                Zone::ring(grid, center, 0.0, (size / 16) as f64, metric),
+            )
+    }
+    fn seek_threaded(&self, draw_path_mut: Arc<Mutex<(Color, Vec<Point>)>>, index: usize) {
+        let g = &self.grid;
+        let (seed, arena, goal) = (
+            &self.zones["seed"],
+            &self.zones["arena"],
+            &self.zones["goal"],
+        );
+        let (mut total, mut lived, mut steps, mut aged, mut crashed) = (0, 0, 0, 0, 0);
 
         'main: loop {
             total = total + 1;
             let mut dir = Compass::rand();
-            let startx = size / 2 + rand::random::<usize>() % (size / 16) - (size / 32);
-            let starty = size / 2 + rand::random::<usize>() % (size / 16) - (size / 32);
-            let mut p = Point(starty, startx);
+            let mut p = seed.sample().unwrap();
             let mut path = Vec::<Point>::new();
             let lucky = rand::random::<f64>() < 0.001;
             let turniness = 32; //rand::random::<usize>() % 64 + 2;
@@ -72,9 +101,6 @@ impl Simpleburg {
                 if lucky {
                     path.push(p);
                 };
-                if Point::distance(center, p) > (size / 2 - 2) as f64 {
-                    break;
-                }
 
                 let roll = rand::random::<usize>() % turniness;
                 if roll == 0 {
@@ -85,26 +111,20 @@ impl Simpleburg {
                 }
 
                 {
-                    let g = g_mut.read().unwrap();
-                    let next = g.step(p, dir);
-                    let mut friendly = false;
-                    let mut unfriendly = false;
-                    for n_dir in Compass::all() {
-                        let neighbor = g.get(g.step(p, n_dir));
-                        if neighbor == index {
-                            friendly = true
-                        } else if neighbor != usize::MAX {
-                            unfriendly = true
-                        }
+                    if !arena.contains(p) {
+                        break 'seek;
                     }
-                    if friendly && !unfriendly {
+                    let next = g.step(p, dir);
+                    //Stick only when everything around is our own
+                    let n = g.get_neighborhood(p);
+                    let friends = Mask::of(&n, |s| s == Particle(index));
+                    if !friends.is_empty() && Mask::of(&n, |s| s != EMPTY) == friends {
                         break 'seek;
                     }
-                    if g.get(next) != usize::MAX {
+                    if g.get(next) != EMPTY {
                         dir = dir.right();
                         continue 'seek;
                     }
-                    drop(g);
                     p = next;
                 }
             }
@@ -113,13 +133,13 @@ impl Simpleburg {
                 draw_path.1 = path;
                 draw_path.0 = color::COLORS[index as usize];
             }
+            //Someone else got here first
+            if !g.set_if(p, |s| s == EMPTY, Particle(index)) {
+                crashed += 1;
+                continue 'main;
+            }
             lived = lived + 1;
-            let mut g = g_mut.write().unwrap();
-            g.set(p, index);
-            drop(g);
-            let mut draw_g = draw_grid_mut.lock().unwrap();
-            draw_g.set(p, color);
-            if Point::distance(center, p) < (size / 16) as f64 {
+            if goal.contains(p) {
                 break 'main;
             }
         }
@@ -130,22 +150,23 @@ impl Simpleburg {
     }
 }
 
-impl Petersburg for Simpleburg {
+impl<G> Petersburg for Simpleburg<G>
+where
+    G: ThreadedGrid<Particle> + BuildGrid<Particle> + Draw + Send + Sync + 'static,
+{
     fn run(&self) {
         crossbeam::scope(|scope| {
             for i in 0..self.args.num_threads {
-                let draw_grid_m = Arc::clone(&(self.draw_grid_m));
                 let draw_path_m = Arc::clone(&(self.draw_path_m));
-                let grid_rw = Arc::clone(&(self.grid_rw));
                 scope.spawn(move |_| {
-                    self.seek_threaded(grid_rw, draw_grid_m, draw_path_m, i);
+                    self.seek_threaded(draw_path_m, i);
                 });
             }
         })
         .unwrap();
     }
     fn draw(&self, context: &Context) {
-        self.draw_grid_m.lock().unwrap().draw(context);
+        self.grid.draw(context);
         let pair = self.draw_path_m.lock().unwrap();
         let (color, path) = (pair.0, &pair.1);
         draw_utils::path_helper(context, self.args.size, color, path);
@@ -5,4 +5,16 @@ pub mod local;
 pub use self::local::*;
 pub mod grid;
 pub use self::grid::*;
+pub mod ascii;
+pub use self::ascii::types::*;
+pub mod components;
+pub use self::components::types::*;
 pub mod displays;
+pub mod image;
+pub mod pathing;
+pub mod sight;
+pub use self::sight::types::*;
+pub mod transform;
+pub mod zone;
+pub use self::pathing::types::*;
+pub use self::zone::types::*;
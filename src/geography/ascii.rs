@@ -0,0 +1,283 @@
+//Grids drawn as text, one character a square, for setting up scenarios by hand
+use super::*;
+use std::path::Path;
+
+pub mod types {
+    pub use super::{Legend, Scene};
+}
+
+//Which character stands for which square. The wall character, if any, goes into a separate wall layer like a Maze's,
+//with the blank square left underneath it.
+#[derive(Clone, Debug)]
+pub struct Legend<T> {
+    entries: Vec<(char, T)>,
+    blank: T,
+    wall: Option<char>,
+}
+
+impl<T: Copy + PartialEq> Legend<T> {
+    pub fn new(blank: T) -> Self {
+        Self {
+            entries: vec![],
+            blank,
+            wall: None,
+        }
+    }
+    pub fn with(mut self, c: char, t: T) -> Self {
+        self.entries.push((c, t));
+        self
+    }
+    pub fn wall(mut self, c: char) -> Self {
+        self.wall = Some(c);
+        self
+    }
+    fn square(&self, c: char) -> Option<T> {
+        self.entries.iter().find(|(e, _)| *e == c).map(|(_, t)| *t)
+    }
+    //The first character listed for t wins, so aliases can be added after the one that should be written out
+    fn char(&self, t: T) -> char {
+        self.entries
+            .iter()
+            .find(|(_, e)| *e == t)
+            .map_or('?', |(c, _)| *c)
+    }
+}
+
+#[derive(Clone)]
+pub struct Scene<T: Copy> {
+    pub cells: WrappedGrid<T>,
+    pub walls: Option<WrappedGrid<bool>>,
+}
+
+impl<T: Copy> Scene<T> {
+    pub fn build<G: BuildGrid<T> + ThreadedGrid<T>>(&self) -> G {
+        self.build_with(Partition::default(), Layout::default())
+    }
+    pub fn build_with<G: BuildGrid<T> + ThreadedGrid<T>>(
+        &self,
+        partition: Partition,
+        layout: Layout,
+    ) -> G {
+        let Rect { width, height, .. } = self.cells.bounds();
+        let grid = G::build_with(
+            width,
+            height,
+            self.cells.get(Point(0, 0)),
+            partition,
+            layout,
+        );
+        for (p, t) in self.cells.iter() {
+            grid.set(p, t);
+        }
+        grid
+    }
+    pub fn is_wall(&self, p: Point) -> bool {
+        self.walls.as_ref().is_some_and(|walls| walls.get(p))
+    }
+    //Walls are drawn over whatever's under them
+    pub fn to_ascii(&self, legend: &Legend<T>) -> String
+    where
+        T: PartialEq,
+    {
+        draw(&self.cells, legend, |p| self.is_wall(p))
+    }
+}
+
+//Blank lines around the picture and any indentation shared by all its lines are ignored, so scenes can be written
+//inline in an indented string literal. Every row has to be the same width, trailing spaces included.
+pub fn parse<T: Copy + PartialEq>(text: &str, legend: &Legend<T>) -> Result<Scene<T>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let drawn = |l: &&str| !l.trim().is_empty();
+    let first = lines.iter().position(drawn).ok_or("Nothing to parse")?;
+    let last = lines.iter().rposition(drawn).unwrap();
+    let lines = &lines[first..=last];
+    let indent = lines
+        .iter()
+        .filter(|l| drawn(l))
+        .map(|l| l.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap();
+    let rows: Vec<Vec<char>> = lines
+        .iter()
+        .map(|l| l.chars().skip(indent).collect())
+        .collect();
+    let width = rows[0].len();
+    if let Some(y) = rows.iter().position(|row| row.len() != width) {
+        return Err(format!(
+            "Row {} is {} wide, but the first row is {}",
+            y,
+            rows[y].len(),
+            width
+        ));
+    }
+    let mut cells = WrappedGrid::new(width, rows.len(), legend.blank);
+    let mut walls = legend
+        .wall
+        .map(|_| WrappedGrid::new(width, rows.len(), false));
+    for p in cells.bounds().points() {
+        let c = rows[p.1][p.0];
+        if Some(c) == legend.wall {
+            walls.as_mut().unwrap().set(p, true);
+        } else {
+            let t = legend
+                .square(c)
+                .ok_or_else(|| format!("{:?} at {} isn't in the legend", c, p))?;
+            cells.set(p, t);
+        }
+    }
+    Ok(Scene { cells, walls })
+}
+
+pub fn load<T: Copy + PartialEq, P: AsRef<Path>>(
+    path: P,
+    legend: &Legend<T>,
+) -> Result<Scene<T>, String> {
+    parse(
+        &std::fs::read_to_string(path).map_err(|e| e.to_string())?,
+        legend,
+    )
+}
+
+//One line a row, with '?' for anything the legend has no character for
+pub fn to_ascii<T, G>(grid: &G, legend: &Legend<T>) -> String
+where
+    T: Copy + PartialEq,
+    G: Grid<T> + ?Sized,
+{
+    draw(grid, legend, |_| false)
+}
+
+fn draw<T, G, W>(grid: &G, legend: &Legend<T>, is_wall: W) -> String
+where
+    T: Copy + PartialEq,
+    G: Grid<T> + ?Sized,
+    W: Fn(Point) -> bool,
+{
+    let snapshot = grid.snapshot();
+    let Rect { width, .. } = snapshot.bounds();
+    let mut text = String::new();
+    for (i, (p, t)) in snapshot.iter().enumerate() {
+        match legend.wall {
+            Some(c) if is_wall(p) => text.push(c),
+            _ => text.push(legend.char(t)),
+        }
+        if (i + 1) % width == 0 {
+            text.push('\n');
+        }
+    }
+    text
+}
+
+//Scenes drawn as text, parsed, run and drawn back out again:
+//    cargo test ascii::tests
+#[cfg(test)]
+mod tests {
+    use crate::geography::*;
+
+    fn legend() -> Legend<char> {
+        Legend::new('.')
+            .with('.', '.')
+            .with('a', 'a')
+            .with('b', 'b')
+            .wall('#')
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = "
+            ..#..a
+            .a#...
+            ..##.b
+        ";
+        let scene = ascii::parse(text, &legend()).unwrap();
+        assert_eq!(scene.cells.bounds(), Rect::new(Point(0, 0), 6, 3));
+        assert!(scene.is_wall(Point(2, 0)) && scene.is_wall(Point(3, 2)));
+        assert_eq!(scene.cells.get(Point(2, 0)), '.');
+        assert_eq!(scene.cells.get(Point(5, 2)), 'b');
+        assert_eq!(scene.to_ascii(&legend()), "..#..a\n.a#...\n..##.b\n");
+        //Without the wall layer, the blank underneath shows through
+        assert_eq!(
+            ascii::to_ascii(&scene.cells, &legend()),
+            ".....a\n.a....\n.....b\n"
+        );
+    }
+
+    //Mazeburg's stick rule: come to rest next to kin, as long as nobody else is next to you too
+    fn seek(grid: &RwGrid<char>, start: Point, dir: Compass) -> PathResult {
+        let mut lifetime = 0;
+        grid.path(start, &mut |n| {
+            let kin = Mask::of(&n, |c| c == 'a');
+            let occupied = Mask::of(&n, |c| c != '.');
+            lifetime += 1;
+            if !kin.is_empty() && (occupied & !kin).is_empty() {
+                StepResult::Stick('a')
+            } else if lifetime > 20 {
+                StepResult::Die
+            } else {
+                StepResult::Step(Some(dir))
+            }
+        })
+    }
+
+    #[test]
+    fn walker_sticks_to_kin_only() {
+        let scene = ascii::parse(
+            "
+            ..........
+            ......a...
+            ..........
+            ......a...
+            .....b....
+            ",
+            &legend(),
+        )
+        .unwrap();
+        let grid: RwGrid<char> = scene.build();
+        assert!(matches!(
+            seek(&grid, Point(0, 1), Compass::E),
+            PathResult::Stuck(Point(5, 1))
+        ));
+        //b is next to the near side of the lower a, so it has to walk over it and stick on the far side
+        assert!(matches!(
+            seek(&grid, Point(0, 3), Compass::E),
+            PathResult::Stuck(Point(7, 3))
+        ));
+        assert_eq!(
+            ascii::to_ascii(&grid, &legend()),
+            "..........\n.....aa...\n..........\n......aa..\n.....b....\n"
+        );
+    }
+
+    #[test]
+    fn indentation_and_blank_lines() {
+        let scene = ascii::parse("\n\n      ab\n      ..\n\n", &legend()).unwrap();
+        assert_eq!(scene.to_ascii(&legend()), "ab\n..\n");
+        //Only what's shared by every line is taken off, so any more is squares
+        let error = ascii::parse("  .a\n   b", &legend()).err().unwrap();
+        assert_eq!(error, "' ' at (0,1) isn't in the legend");
+        let error = ascii::parse("  .a\n   ab", &legend()).err().unwrap();
+        assert_eq!(error, "Row 1 is 3 wide, but the first row is 2");
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            ascii::parse("  \n \n", &legend()).err().unwrap(),
+            "Nothing to parse"
+        );
+        assert_eq!(
+            ascii::parse("..\n...\n", &legend()).err().unwrap(),
+            "Row 1 is 3 wide, but the first row is 2"
+        );
+        assert_eq!(
+            ascii::parse("..\n.c\n", &legend()).err().unwrap(),
+            "'c' at (1,1) isn't in the legend"
+        );
+        //Walls need a wall character
+        let no_walls = Legend::new('.').with('.', '.');
+        assert!(ascii::parse("#", &no_walls)
+            .err()
+            .unwrap()
+            .contains("isn't in the legend"));
+    }
+}
@@ -0,0 +1,164 @@
+//Connected-component labeling: which cells are joined up, through moves that keep landing on the same key
+use super::*;
+use std::collections::VecDeque;
+
+pub mod types {
+    pub use super::{Cluster, Components};
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Cluster<K> {
+    pub key: K,
+    //Where labeling first found it
+    pub seed: Point,
+    pub size: usize,
+    //Smallest rect holding all of it, which can hang off the edge and wrap round like the cluster does
+    pub bounds: Rect,
+    //Sides of its cells that face a cell outside it, counting only the four cardinal sides
+    pub perimeter: usize,
+}
+
+pub struct Components<K> {
+    labels: WrappedGrid<Option<usize>>,
+    //Indexed by label
+    pub clusters: Vec<Cluster<K>>,
+}
+
+impl<K: Copy + PartialEq> Components<K> {
+    pub fn label(&self, p: Point) -> Option<usize> {
+        self.labels.get(p)
+    }
+    pub fn cluster_at(&self, p: Point) -> Option<&Cluster<K>> {
+        self.label(p).map(|label| &self.clusters[label])
+    }
+    pub fn of(&self, key: K) -> impl Iterator<Item = &Cluster<K>> {
+        self.clusters
+            .iter()
+            .filter(move |cluster| cluster.key == key)
+    }
+    pub fn len(&self) -> usize {
+        self.clusters.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.clusters.is_empty()
+    }
+}
+
+//Cells are joined when one of the moves takes one to the other and key gives them the same Some. Cells keyed None
+//aren't in any cluster. Pass Cardinal::all() for four-way connectivity and Compass::all() for eight-way.
+//Works from a snapshot, so labels are in its coordinates - the grid's own, for all but unbounded grids.
+pub fn label<T, G, D, K, F>(grid: &G, moves: &[D], key: F) -> Components<K>
+where
+    T: Copy,
+    G: Grid<T> + ?Sized,
+    D: Direction,
+    K: Copy + PartialEq,
+    F: Fn(T) -> Option<K>,
+{
+    let snapshot = grid.snapshot();
+    let Rect { width, height, .. } = snapshot.bounds();
+    let keys = snapshot.map(&key);
+    let mut labels = WrappedGrid::new(width, height, None);
+    let mut clusters = Vec::new();
+    let mut queue = VecDeque::new();
+    for seed in keys.bounds().points() {
+        let k = match keys.get(seed) {
+            Some(k) if labels.get(seed).is_none() => k,
+            _ => continue,
+        };
+        let label = clusters.len();
+        let (mut size, mut perimeter) = (0, 0);
+        //Extent in offsets from the seed, so clusters that wrap round an edge stay in one piece
+        let (mut min, mut max) = (Offset::ZERO, Offset::ZERO);
+        labels.set(seed, Some(label));
+        queue.push_back((seed, Offset::ZERO));
+        while let Some((p, o)) = queue.pop_front() {
+            size += 1;
+            min = Offset(min.0.min(o.0), min.1.min(o.1));
+            max = Offset(max.0.max(o.0), max.1.max(o.1));
+            perimeter += Cardinal::all()
+                .into_iter()
+                .filter(|dir| keys.get(keys.step(p, *dir)) != Some(k))
+                .count();
+            for dir in moves {
+                let next = keys.step(p, *dir);
+                if keys.get(next) == Some(k) && labels.get(next).is_none() {
+                    labels.set(next, Some(label));
+                    queue.push_back((next, o + dir.offset()));
+                }
+            }
+        }
+        let bounds = Rect::new(
+            keys.offset(seed, min),
+            usize::min((max.0 - min.0) as usize + 1, width),
+            usize::min((max.1 - min.1) as usize + 1, height),
+        );
+        clusters.push(Cluster {
+            key: k,
+            seed,
+            size,
+            bounds,
+            perimeter,
+        });
+    }
+    Components { labels, clusters }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(cells: &[Point]) -> WrappedGrid<u8> {
+        let mut grid = WrappedGrid::new(6, 4, 0);
+        for p in cells {
+            grid.set(*p, 1);
+        }
+        grid
+    }
+
+    fn ones(value: u8) -> Option<u8> {
+        (value == 1).then_some(1)
+    }
+
+    #[test]
+    fn clusters_join_across_the_seam() {
+        let grid = grid(&[Point(5, 1), Point(0, 1), Point(5, 2)]);
+        let components = label(&grid, &Cardinal::all(), ones);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components.label(Point(5, 2)), components.label(Point(0, 1)));
+        assert_eq!(components.label(Point(1, 1)), None);
+        let cluster = components.cluster_at(Point(5, 1)).unwrap();
+        assert_eq!((cluster.seed, cluster.size), (Point(0, 1), 3));
+        assert_eq!(cluster.bounds, Rect::new(Point(5, 1), 2, 2));
+    }
+
+    #[test]
+    fn corners_only_join_eight_ways() {
+        let grid = grid(&[Point(0, 0), Point(5, 3)]);
+        assert_eq!(label(&grid, &Cardinal::all(), ones).len(), 2);
+        let components = label(&grid, &Compass::all(), ones);
+        assert_eq!(components.len(), 1);
+        assert_eq!(components.clusters[0].bounds, Rect::new(Point(5, 3), 2, 2));
+    }
+
+    #[test]
+    fn perimeter_counts_outward_sides() {
+        let components = label(&grid(&[Point(4, 0)]), &Cardinal::all(), ones);
+        assert_eq!(components.clusters[0].perimeter, 4);
+        let square = [Point(1, 1), Point(2, 1), Point(1, 2), Point(2, 2)];
+        let components = label(&grid(&square), &Cardinal::all(), ones);
+        assert_eq!(components.clusters[0].perimeter, 8);
+        //Diagonal moves join cells without closing up any of their sides
+        let components = label(&grid(&[Point(1, 1), Point(2, 2)]), &Compass::all(), ones);
+        assert_eq!(components.clusters[0].perimeter, 8);
+        //A row all the way round has no ends, and is only as wide as the world
+        let row: Vec<_> = (0..6).map(|x| Point(x, 3)).collect();
+        let cluster = label(&grid(&row), &Cardinal::all(), ones).clusters[0];
+        assert_eq!(cluster.perimeter, 12);
+        assert_eq!(cluster.bounds.width, 6);
+        //The zeroes wrap round the row too, into one cluster either side of it
+        let zeroes = label(&grid(&row), &Cardinal::all(), Some);
+        assert_eq!(zeroes.of(0).count(), 1);
+        assert_eq!(zeroes.of(0).next().unwrap().perimeter, 12);
+    }
+}
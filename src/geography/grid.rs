@@ -1,14 +1,53 @@
-
-
 pub mod point;
 pub use self::point::Point;
+pub mod rect;
+pub use self::rect::{Rect, RectPoints};
 
 pub mod grid;
 pub use self::grid::types::*;
+pub mod contention;
+pub use self::contention::types::*;
+pub mod feed;
+pub use self::feed::types::*;
+pub mod kind;
+pub use self::kind::types::*;
+pub mod layout;
+pub use self::layout::types::*;
+pub mod metric;
+pub use self::metric::types::*;
+pub mod offset;
+pub use self::offset::types::*;
+pub mod partition;
+pub use self::partition::types::*;
+pub mod pyramid;
+pub use self::pyramid::types::*;
+pub mod sampler;
+pub use self::sampler::types::*;
 
+pub mod atomic;
+pub use self::atomic::*;
+pub mod buffered;
+pub use self::buffered::*;
+pub mod chunked;
+pub use self::chunked::*;
+pub mod layered;
+pub use self::layered::*;
+pub mod locked;
+pub use self::locked::*;
+pub mod overview;
+pub use self::overview::*;
 pub mod regional;
 pub use self::regional::*;
 pub mod rw;
 pub use self::rw::*;
+pub mod sampled;
+pub use self::sampled::*;
+pub mod view;
+pub use self::view::*;
+pub mod watched;
+pub use self::watched::*;
 pub mod wrapped;
 pub use self::wrapped::*;
+
+#[cfg(all(test, loom))]
+mod loom_tests;
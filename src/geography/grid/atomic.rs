@@ -0,0 +1,3 @@
+mod atomic_grid;
+mod graphics;
+pub use self::atomic_grid::AtomicGrid;
@@ -0,0 +1,156 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use crossbeam::atomic::AtomicCell;
+use std::sync::RwLock;
+
+//Rows of the grid handed out per region. These aren't the units of locking, it's just how work gets split up.
+const ROWS_PER_REGION: usize = 16;
+//Row y is guarded by stripe y % STRIPES
+const STRIPES: usize = 64;
+
+//Every square is its own atomic, so conditional writes are compare-and-swap loops. Each row shares one of a few
+//striped locks: reads and writes take it shared, which only costs a counter bump unless a transaction is holding it
+//exclusively, so nothing ever sees half a transaction. A transaction only shuts out the rows it touches.
+pub struct AtomicGrid<T> {
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) layout: Layout,
+    pub(super) squares: Vec<AtomicCell<T>>,
+    stripes: Vec<RwLock<()>>,
+}
+
+impl<T: Copy> Grid<T> for AtomicGrid<T> {
+    #[inline(always)]
+    fn get(&self, p: Point) -> T {
+        let _shared = self.stripe(p).read().unwrap();
+        self.squares[self.index(p)].load()
+    }
+    //The three rows are locked together, so the neighborhood is all from one moment
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        let mut rows = [-1, 0, 1].map(|dy| self.stripe_of(p.1.wrapping_add_signed(dy)));
+        let rows = super::super::grid::sorted_unique(&mut rows);
+        let mut shared = [None, None, None];
+        for (guard, stripe) in shared.iter_mut().zip(rows.iter()) {
+            *guard = Some(self.stripes[*stripe].read().unwrap());
+        }
+        Neighborhood::around(p).map(|neighbor| self.squares[self.index(neighbor)].load())
+    }
+    #[inline(always)]
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        p.wrapping_offset(o, self.width, self.height)
+    }
+    fn rand(&self) -> Point {
+        Point(roll::usize(self.width), roll::usize(self.height))
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        p1.offset_to(p2, self.width, self.height)
+    }
+    fn bounds(&self) -> Rect {
+        Rect::new(Point(0, 0), self.width, self.height)
+    }
+}
+
+impl<T: Copy + Eq> ThreadedGrid<T> for AtomicGrid<T> {
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let _shared = self.stripe(p).read().unwrap();
+        let square = &self.squares[self.index(p)];
+        let mut current = square.load();
+        loop {
+            let value = match f(current) {
+                None => return current,
+                Some(value) => value,
+            };
+            match square.compare_exchange(current, value) {
+                Ok(previous) => return previous,
+                Err(actual) => current = actual,
+            }
+        }
+    }
+    //No need for a compare-and-swap loop when the new value doesn't depend on the old one
+    fn swap(&self, p: Point, value: T) -> T {
+        let _shared = self.stripe(p).read().unwrap();
+        self.squares[self.index(p)].swap(value)
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        //In stripe order, so two transactions can never each be holding what the other is waiting on
+        let _exclusive: Vec<_> = self
+            .stripes_of(points.iter().copied())
+            .into_iter()
+            .map(|stripe| self.stripes[stripe].write().unwrap())
+            .collect();
+        let mut values: Vec<T> = points
+            .iter()
+            .map(|p| self.squares[self.index(*p)].load())
+            .collect();
+        let commit = f(&mut values);
+        if commit {
+            for (p, value) in points.iter().zip(values) {
+                self.squares[self.index(*p)].store(value);
+            }
+        }
+        commit
+    }
+    fn regions(&self) -> Vec<Rect> {
+        (0..self.height)
+            .step_by(ROWS_PER_REGION)
+            .map(|y| {
+                let rows = usize::min(ROWS_PER_REGION, self.height - y);
+                Rect::new(Point(0, y), self.width, rows)
+            })
+            .collect()
+    }
+}
+
+impl<T: Copy> AtomicGrid<T> {
+    pub fn new(width: usize, height: usize, default: T) -> Self {
+        Self::with_layout(width, height, default, Layout::default())
+    }
+    pub fn with_layout(width: usize, height: usize, default: T, layout: Layout) -> Self {
+        AtomicGrid {
+            width,
+            height,
+            layout,
+            squares: (0..layout.len(width, height))
+                .map(|_| AtomicCell::new(default))
+                .collect(),
+            stripes: (0..STRIPES).map(|_| RwLock::new(())).collect(),
+        }
+    }
+    #[inline(always)]
+    fn index(&self, p: Point) -> usize {
+        let Point(x, y) = p.wrap(self.width, self.height);
+        self.layout.index(self.width, self.height, x, y)
+    }
+    fn stripe(&self, p: Point) -> &RwLock<()> {
+        &self.stripes[self.stripe_of(p.1)]
+    }
+    //Rows off either edge wrap round, like Point::wrap
+    fn stripe_of(&self, y: usize) -> usize {
+        (y as isize).rem_euclid(self.height as isize) as usize % STRIPES
+    }
+    //Sorted and without repeats, ready to be locked in order
+    fn stripes_of<I: Iterator<Item = Point>>(&self, points: I) -> Vec<usize> {
+        let mut stripes: Vec<usize> = points.map(|p| self.stripe_of(p.1)).collect();
+        stripes.sort();
+        stripes.dedup();
+        stripes
+    }
+}
+
+impl<T: Copy> BuildGrid<T> for AtomicGrid<T> {
+    fn build(width: usize, height: usize, default: T) -> Self {
+        Self::new(width, height, default)
+    }
+    fn build_with(width: usize, height: usize, default: T, _: Partition, layout: Layout) -> Self {
+        Self::with_layout(width, height, default, layout)
+    }
+}
@@ -0,0 +1,9 @@
+use super::atomic_grid::*;
+use crate::utils::{color::types::*, draw_utils, draw_utils::Draw};
+use cairo::Context;
+
+impl<T: Colored + Copy> Draw for AtomicGrid<T> {
+    fn draw(&self, context: &Context) {
+        draw_utils::grid_helper(context, self);
+    }
+}
@@ -0,0 +1,3 @@
+mod buffered_grid;
+mod graphics;
+pub use self::buffered_grid::BufferedGrid;
@@ -0,0 +1,186 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Barrier;
+
+//Reads see generation N (the front buffer), writes go to generation N+1 (the back buffer), and nothing
+//written becomes visible until swap(). Anything not written during a generation keeps its value from N-1,
+//so rules should write every square they're responsible for.
+pub struct BufferedGrid<T> {
+    pub(super) buffers: [RwGrid<T>; 2],
+    pub(super) front: AtomicUsize,
+    pub(super) num_workers: usize,
+    barrier: Barrier,
+}
+
+impl<T: Copy> Grid<T> for BufferedGrid<T> {
+    fn get(&self, p: Point) -> T {
+        self.front().get(p)
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        self.front().get_neighborhood(p)
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        self.front().offset(p, o)
+    }
+    fn rand(&self) -> Point {
+        self.front().rand()
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        self.front().between(p1, p2)
+    }
+    fn bounds(&self) -> Rect {
+        self.front().bounds()
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.front().snapshot()
+    }
+}
+
+impl<T: Copy> ThreadedGrid<T> for BufferedGrid<T> {
+    //f sees what's already been written to the next generation
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        self.back().fetch_update(p, f)
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        self.back().transact(points, f)
+    }
+    //Reads from the front and writes to the back, like everything else
+    fn update<F>(&self, p: Point, update: F)
+    where
+        F: Fn(T) -> T,
+    {
+        self.set(p, update(self.get(p)));
+    }
+    fn regions(&self) -> Vec<Rect> {
+        self.front().regions()
+    }
+    fn set_instrumented(&self, on: bool) {
+        self.buffers
+            .iter()
+            .for_each(|buffer| buffer.set_instrumented(on));
+    }
+    fn instrumented(&self) -> bool {
+        self.front().instrumented()
+    }
+    //Both buffers share a partition, so their regions line up
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        let [first, second] = &self.buffers;
+        first
+            .lock_stats()
+            .into_iter()
+            .zip(second.lock_stats())
+            .map(|(a, b)| a.merge(b))
+            .collect()
+    }
+}
+
+impl<T: Copy> BufferedGrid<T> {
+    //num_workers is how many threads will be calling swap(), and the regions are tuned to suit
+    pub fn new(width: usize, height: usize, default: T, num_workers: usize) -> Self {
+        let partition = Partition::auto(width, height, num_workers);
+        BufferedGrid {
+            buffers: [
+                RwGrid::with_partition(width, height, default, partition),
+                RwGrid::with_partition(width, height, default, partition),
+            ],
+            front: AtomicUsize::new(0),
+            num_workers,
+            barrier: Barrier::new(num_workers),
+        }
+    }
+    pub fn front(&self) -> &RwGrid<T> {
+        &self.buffers[self.front.load(Ordering::Acquire)]
+    }
+    pub fn back(&self) -> &RwGrid<T> {
+        &self.buffers[1 - self.front.load(Ordering::Acquire)]
+    }
+    //Blocks until all num_workers threads have called it, then flips the buffers. Nobody is let go until the
+    //flip is done, so reads after swap() always see the new generation.
+    pub fn swap(&self) {
+        if self.barrier.wait().is_leader() {
+            self.front.fetch_xor(1, Ordering::AcqRel);
+        }
+        self.barrier.wait();
+    }
+    //For a single owner, e.g. after seeding the first generation with set(). No need to wait on anyone.
+    pub fn flip(&mut self) {
+        *self.front.get_mut() ^= 1;
+    }
+    //Runs the rule over every square for the given number of generations, with the regions dealt out
+    //between num_workers threads that meet at swap() between generations.
+    pub fn par_generations<F>(&self, generations: usize, rule: F)
+    where
+        T: Send + Sync,
+        F: Fn(Point, Neighborhood<T>) -> T + Sync,
+    {
+        let regions = self.regions();
+        let (regions, rule) = (&regions, &rule);
+        crossbeam::scope(|scope| {
+            for i in 0..self.num_workers {
+                scope.spawn(move |_| {
+                    for _ in 0..generations {
+                        let back = self.back();
+                        for region in regions.iter().skip(i).step_by(self.num_workers) {
+                            for p in region.points() {
+                                back.set(p, rule(p, self.get_neighborhood(p)));
+                            }
+                        }
+                        self.swap();
+                    }
+                });
+            }
+        })
+        .unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_wait_for_the_swap() {
+        let mut grid = BufferedGrid::new(8, 8, 0u32, 1);
+        grid.set(Point(1, 1), 5);
+        assert_eq!(grid.get(Point(1, 1)), 0);
+        assert_eq!(grid.back().get(Point(1, 1)), 5);
+        grid.flip();
+        assert_eq!(grid.get(Point(1, 1)), 5);
+        //Updates build on what's showing, not on what's already been written for next time
+        grid.update(Point(1, 1), |v| v + 1);
+        grid.update(Point(1, 1), |v| v + 1);
+        assert_eq!(grid.back().get(Point(1, 1)), 6);
+        grid.swap();
+        assert_eq!(grid.get(Point(1, 1)), 6);
+        //Skipping a square for a generation brings back what it was two generations ago
+        grid.swap();
+        assert_eq!(grid.get(Point(1, 1)), 5);
+    }
+
+    #[test]
+    fn generations_only_see_the_last_one() {
+        let mut grid = BufferedGrid::new(40, 24, 0u32, 4);
+        grid.set(Point(3, 3), 1);
+        grid.flip();
+        //If anyone read a square already written this generation, it'd get ahead of the rest
+        grid.par_generations(5, |_, neighborhood| {
+            neighborhood.into_iter().max().unwrap() + 1
+        });
+        let snapshot = grid.snapshot();
+        for (p, value) in snapshot.iter() {
+            let steps = Point(3, 3).offset_to(p, 40, 24).delta();
+            let distance = usize::max(steps.0, steps.1) as u32;
+            assert_eq!(value, if distance <= 5 { 6 } else { 5 }, "{p}");
+        }
+    }
+}
@@ -0,0 +1,9 @@
+use super::buffered_grid::*;
+use crate::utils::{color::types::*, draw_utils, draw_utils::Draw};
+use cairo::Context;
+
+impl<T: Colored + Copy> Draw for BufferedGrid<T> {
+    fn draw(&self, context: &Context) {
+        draw_utils::regions_helper(context, self);
+    }
+}
@@ -0,0 +1,3 @@
+mod chunked_grid;
+mod graphics;
+pub use self::chunked_grid::ChunkedGrid;
@@ -0,0 +1,250 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+//Chunks are CHUNK_SIZE squares on a side. Like the default partition, this is a perf-tuning const.
+pub(super) const CHUNK_SIZE: usize = 64;
+pub(super) const CHUNK_AREA: usize = CHUNK_SIZE * CHUNK_SIZE;
+
+pub(super) type ChunkKey = (isize, isize);
+type Chunk<T> = Arc<RwLock<Vec<T>>>;
+
+//An unbounded grid. Coordinates are read as signed, so Point(usize::MAX, 0) is one step west of the origin.
+//Chunks are only allocated on the first write into them; reads of anything else return the default.
+pub struct ChunkedGrid<T> {
+    pub(super) default: T,
+    pub(super) chunks: RwLock<HashMap<ChunkKey, Chunk<T>>>,
+}
+
+impl<T: Copy> Grid<T> for ChunkedGrid<T> {
+    fn get(&self, p: Point) -> T {
+        let (key, index_in_chunk) = Self::map_coordinates(p);
+        match self.chunk(key) {
+            Some(chunk) => chunk.read().unwrap()[index_in_chunk],
+            None => self.default,
+        }
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        Neighborhood::around(p).map(|neighbor| self.get(neighbor))
+    }
+    //Points are two's complement, so plain wrapping addition is already right
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        p + o
+    }
+    fn rand(&self) -> Point {
+        match self.occupied() {
+            None => Point(0, 0),
+            Some(((min_x, min_y), (max_x, max_y))) => Self::point(
+                min_x + roll::usize((max_x - min_x) as usize) as isize,
+                min_y + roll::usize((max_y - min_y) as usize) as isize,
+            ),
+        }
+    }
+    fn bounds(&self) -> Rect {
+        Self::rect_of(self.occupied())
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        //Holding the map lock keeps new chunks from appearing halfway through
+        let chunks = self.chunks.read().unwrap();
+        let bounds = Self::rect_of(Self::occupied_by(chunks.keys()));
+        let mut snapshot = WrappedGrid::new(bounds.width, bounds.height, self.default);
+        for (&(chunk_x, chunk_y), chunk) in chunks.iter() {
+            let corner = Self::point(chunk_x * CHUNK_SIZE as isize, chunk_y * CHUNK_SIZE as isize);
+            for (j, value) in chunk.read().unwrap().iter().enumerate() {
+                let p = Point(
+                    corner.0.wrapping_add(j % CHUNK_SIZE),
+                    corner.1.wrapping_add(j / CHUNK_SIZE),
+                );
+                snapshot.set(bounds.local(p), *value);
+            }
+        }
+        snapshot
+    }
+    //Nothing wraps, so it's straight across
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        Offset(
+            p2.0.wrapping_sub(p1.0) as isize,
+            p2.1.wrapping_sub(p1.1) as isize,
+        )
+    }
+}
+
+impl<T: Copy> ThreadedGrid<T> for ChunkedGrid<T> {
+    fn regions(&self) -> Vec<Rect> {
+        let corner =
+            |&(x, y): &ChunkKey| Self::point(x * CHUNK_SIZE as isize, y * CHUNK_SIZE as isize);
+        self.chunks
+            .read()
+            .unwrap()
+            .keys()
+            .map(|key| Rect::new(corner(key), CHUNK_SIZE, CHUNK_SIZE))
+            .collect()
+    }
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let (key, index_in_chunk) = Self::map_coordinates(p);
+        //No point allocating a chunk for a write that was never going to happen
+        if self.chunk(key).is_none() && f(self.default).is_none() {
+            return self.default;
+        }
+        let chunk = self.chunk_or_insert(key);
+        let mut chunk = chunk.write().unwrap();
+        let pre_existing = chunk[index_in_chunk];
+        if let Some(value) = f(pre_existing) {
+            chunk[index_in_chunk] = value;
+        }
+        pre_existing
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let cells: Vec<_> = points.iter().map(|p| Self::map_coordinates(*p)).collect();
+        let chunks: HashMap<ChunkKey, Chunk<T>> = cells
+            .iter()
+            .map(|(key, _)| (*key, self.chunk_or_insert(*key)))
+            .collect();
+        super::super::grid::transact_regions(&cells, |key| chunks[&key].write().unwrap(), f)
+    }
+}
+
+impl<T: Copy> ChunkedGrid<T> {
+    pub fn new(default: T) -> Self {
+        ChunkedGrid {
+            default,
+            chunks: RwLock::new(HashMap::new()),
+        }
+    }
+    #[inline(always)]
+    pub fn point(x: isize, y: isize) -> Point {
+        Point(x as usize, y as usize)
+    }
+    #[inline(always)]
+    pub fn signed(p: Point) -> (isize, isize) {
+        (p.0 as isize, p.1 as isize)
+    }
+    //Top left and bottom right (exclusive) corners of the allocated chunks, or None if nothing has been written
+    pub fn occupied(&self) -> Option<((isize, isize), (isize, isize))> {
+        Self::occupied_by(self.chunks.read().unwrap().keys())
+    }
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.read().unwrap().len()
+    }
+    fn occupied_by<'a, I>(keys: I) -> Option<((isize, isize), (isize, isize))>
+    where
+        I: Iterator<Item = &'a ChunkKey> + Clone,
+    {
+        let (min_x, max_x) = (
+            keys.clone().map(|k| k.0).min()?,
+            keys.clone().map(|k| k.0).max()?,
+        );
+        let (min_y, max_y) = (keys.clone().map(|k| k.1).min()?, keys.map(|k| k.1).max()?);
+        let size = CHUNK_SIZE as isize;
+        Some((
+            (min_x * size, min_y * size),
+            ((max_x + 1) * size, (max_y + 1) * size),
+        ))
+    }
+    fn rect_of(occupied: Option<((isize, isize), (isize, isize))>) -> Rect {
+        match occupied {
+            None => Rect::new(Point(0, 0), 0, 0),
+            Some(((min_x, min_y), (max_x, max_y))) => Rect::new(
+                Self::point(min_x, min_y),
+                (max_x - min_x) as usize,
+                (max_y - min_y) as usize,
+            ),
+        }
+    }
+    pub(super) fn map_coordinates(p: Point) -> (ChunkKey, usize) {
+        let (x, y) = Self::signed(p);
+        let size = CHUNK_SIZE as isize;
+        let key = (x.div_euclid(size), y.div_euclid(size));
+        let (x_in_chunk, y_in_chunk) = (x.rem_euclid(size) as usize, y.rem_euclid(size) as usize);
+        (key, y_in_chunk * CHUNK_SIZE + x_in_chunk)
+    }
+    pub(super) fn chunk(&self, key: ChunkKey) -> Option<Chunk<T>> {
+        self.chunks.read().unwrap().get(&key).map(Arc::clone)
+    }
+    fn chunk_or_insert(&self, key: ChunkKey) -> Chunk<T> {
+        if let Some(chunk) = self.chunk(key) {
+            return chunk;
+        }
+        let default = self.default;
+        let mut chunks = self.chunks.write().unwrap();
+        let chunk = chunks
+            .entry(key)
+            .or_insert_with(|| Arc::new(RwLock::new(vec![default; CHUNK_AREA])));
+        Arc::clone(chunk)
+    }
+}
+
+//Unbounded, so the dimensions are only a hint
+impl<T: Copy> BuildGrid<T> for ChunkedGrid<T> {
+    fn build(_width: usize, _height: usize, default: T) -> Self {
+        Self::new(default)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_until_written() {
+        let grid = ChunkedGrid::new(0u8);
+        assert_eq!(grid.bounds(), Rect::new(Point(0, 0), 0, 0));
+        assert_eq!(grid.rand(), Point(0, 0));
+        assert_eq!(grid.get(ChunkedGrid::<u8>::point(-5, 1000)), 0);
+        //A write that doesn't change anything doesn't allocate
+        grid.fetch_update(Point(3, 3), |_| None);
+        assert_eq!(grid.num_chunks(), 0);
+        assert_eq!(grid.snapshot().bounds(), Rect::new(Point(0, 0), 0, 0));
+    }
+
+    #[test]
+    fn bounds_cover_sparse_chunks() {
+        let grid = ChunkedGrid::new(0u8);
+        let (west, east) = (ChunkedGrid::<u8>::point(-1, -1), Point(130, 5));
+        grid.set(west, 1);
+        grid.set(east, 2);
+        assert_eq!(grid.num_chunks(), 2);
+        let size = CHUNK_SIZE as isize;
+        assert_eq!(grid.occupied(), Some(((-size, -size), (3 * size, size))));
+        let bounds = grid.bounds();
+        assert_eq!(bounds.origin, ChunkedGrid::<u8>::point(-size, -size));
+        assert_eq!(
+            (bounds.width, bounds.height),
+            (4 * CHUNK_SIZE, 2 * CHUNK_SIZE)
+        );
+        assert!(bounds.contains(west) && bounds.contains(east));
+        let snapshot = grid.snapshot();
+        assert_eq!(snapshot.get(bounds.local(west)), 1);
+        assert_eq!(snapshot.get(bounds.local(east)), 2);
+        assert_eq!(snapshot.get(bounds.local(Point(64, 0))), 0);
+        //Nothing wraps, so going west of the origin is a short step rather than all the way round
+        assert_eq!(grid.between(west, Point(1, 0)), Offset(2, 1));
+    }
+
+    #[test]
+    fn rand_stays_in_the_occupied_chunks() {
+        let grid = ChunkedGrid::new(0u8);
+        grid.set(ChunkedGrid::<u8>::point(-70, 10), 1);
+        let size = CHUNK_SIZE as isize;
+        for _ in 0..1000 {
+            let (x, y) = ChunkedGrid::<u8>::signed(grid.rand());
+            assert!((-2 * size..-size).contains(&x) && (0..size).contains(&y));
+        }
+        //Gaps between chunks are in range too, as long as they're inside the bounds
+        grid.set(Point(200, 10), 1);
+        let bounds = grid.bounds();
+        for _ in 0..1000 {
+            assert!(bounds.contains(grid.rand()));
+        }
+    }
+}
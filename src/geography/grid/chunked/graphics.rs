@@ -0,0 +1,10 @@
+use super::chunked_grid::*;
+use crate::utils::{color::types::*, draw_utils, draw_utils::Draw};
+use cairo::Context;
+
+impl<T: Colored + Copy> Draw for ChunkedGrid<T> {
+    //Only the occupied bounding box is fitted to the window, so the view grows along with the world
+    fn draw(&self, context: &Context) {
+        draw_utils::regions_helper(context, self);
+    }
+}
@@ -0,0 +1,151 @@
+use super::*;
+use crate::sync::{AtomicBool, AtomicU64, Ordering, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::fmt::{Display, Formatter, Result};
+use std::sync::TryLockError;
+use std::time::{Duration, Instant};
+
+pub mod types {
+    pub use super::RegionStats;
+}
+
+//How many regions report() lists individually
+const HOTTEST_SHOWN: usize = 5;
+
+//Counts for one region lock since instrumentation was last turned on
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RegionStats {
+    pub rect: Rect,
+    pub acquisitions: u64,
+    //Acquisitions that had to wait for someone else to let go
+    pub contended: u64,
+    pub wait: Duration,
+}
+
+impl RegionStats {
+    pub fn contention_rate(&self) -> f64 {
+        if self.acquisitions == 0 {
+            0.0
+        } else {
+            self.contended as f64 / self.acquisitions as f64
+        }
+    }
+    //For combining the same region across several grids, e.g. both buffers of a BufferedGrid
+    pub fn merge(self, other: RegionStats) -> RegionStats {
+        RegionStats {
+            rect: self.rect,
+            acquisitions: self.acquisitions + other.acquisitions,
+            contended: self.contended + other.contended,
+            wait: self.wait + other.wait,
+        }
+    }
+}
+
+impl Display for RegionStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(
+            f,
+            "{} {}x{}: {} acquisitions, {} contended ({:.1}%), {:.2?} waiting",
+            self.rect.origin,
+            self.rect.width,
+            self.rect.height,
+            self.acquisitions,
+            self.contended,
+            self.contention_rate() * 100.0,
+            self.wait
+        )
+    }
+}
+
+//Totals, then the regions that spent longest waiting
+pub fn report(stats: &[RegionStats]) {
+    if stats.is_empty() {
+        println!("No lock stats - either instrumentation is off or this grid has no region locks");
+        return;
+    }
+    let total = stats[1..].iter().fold(stats[0], |total, s| total.merge(*s));
+    println!(
+        "{} regions: {} acquisitions, {} contended ({:.1}%), {:.2?} waiting",
+        stats.len(),
+        total.acquisitions,
+        total.contended,
+        total.contention_rate() * 100.0,
+        total.wait
+    );
+    let mut hottest = stats.to_vec();
+    hottest.sort_by_key(|s| std::cmp::Reverse(s.wait));
+    for s in hottest.iter().take(HOTTEST_SHOWN) {
+        println!("\t{}", s);
+    }
+}
+
+//A region lock that can count what happens to it. Counting is off until switched on, and costs one relaxed
+//load per acquisition while it's off.
+pub(super) struct RegionLock<T> {
+    lock: RwLock<T>,
+    acquisitions: AtomicU64,
+    contended: AtomicU64,
+    wait_nanos: AtomicU64,
+}
+
+impl<T> RegionLock<T> {
+    pub(super) fn new(value: T) -> Self {
+        RegionLock {
+            lock: RwLock::new(value),
+            acquisitions: AtomicU64::new(0),
+            contended: AtomicU64::new(0),
+            wait_nanos: AtomicU64::new(0),
+        }
+    }
+    pub(super) fn read(&self, instrumented: &AtomicBool) -> RwLockReadGuard<'_, T> {
+        if !instrumented.load(Ordering::Relaxed) {
+            return self.lock.read().unwrap();
+        }
+        //Try first, so only acquisitions that really block get timed
+        match self.lock.try_read() {
+            Ok(guard) => self.record(None, guard),
+            Err(TryLockError::WouldBlock) => {
+                let start = Instant::now();
+                let guard = self.lock.read().unwrap();
+                self.record(Some(start), guard)
+            }
+            Err(TryLockError::Poisoned(e)) => panic!("{}", e),
+        }
+    }
+    pub(super) fn write(&self, instrumented: &AtomicBool) -> RwLockWriteGuard<'_, T> {
+        if !instrumented.load(Ordering::Relaxed) {
+            return self.lock.write().unwrap();
+        }
+        match self.lock.try_write() {
+            Ok(guard) => self.record(None, guard),
+            Err(TryLockError::WouldBlock) => {
+                let start = Instant::now();
+                let guard = self.lock.write().unwrap();
+                self.record(Some(start), guard)
+            }
+            Err(TryLockError::Poisoned(e)) => panic!("{}", e),
+        }
+    }
+    pub(super) fn stats(&self, rect: Rect) -> RegionStats {
+        RegionStats {
+            rect,
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            wait: Duration::from_nanos(self.wait_nanos.load(Ordering::Relaxed)),
+        }
+    }
+    pub(super) fn reset(&self) {
+        self.acquisitions.store(0, Ordering::Relaxed);
+        self.contended.store(0, Ordering::Relaxed);
+        self.wait_nanos.store(0, Ordering::Relaxed);
+    }
+    //Passes the guard through, so the counting happens after the lock is held and isn't counted as waiting
+    fn record<G>(&self, blocked_since: Option<Instant>, guard: G) -> G {
+        self.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if let Some(start) = blocked_since {
+            self.contended.fetch_add(1, Ordering::Relaxed);
+            let waited = start.elapsed().as_nanos() as u64;
+            self.wait_nanos.fetch_add(waited, Ordering::Relaxed);
+        }
+        guard
+    }
+}
@@ -0,0 +1,83 @@
+use super::*;
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+
+pub mod types {
+    pub use super::{Change, Feed};
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Change<T> {
+    pub p: Point,
+    pub old: T,
+    pub new: T,
+}
+
+//Hands every published change to everyone subscribed. WatchedGrid publishes its writes to one.
+pub struct Feed<T> {
+    subscribers: Mutex<Vec<Sender<Change<T>>>>,
+}
+
+impl<T: Copy + Send> Feed<T> {
+    pub fn new() -> Self {
+        Feed {
+            subscribers: Mutex::new(Vec::new()),
+        }
+    }
+    //Everything published from now on. try_iter() on the receiver gives what's changed since it was last asked,
+    //e.g. once a frame. Dropping the receiver unsubscribes.
+    pub fn subscribe(&self) -> Receiver<Change<T>> {
+        let (sender, receiver) = channel();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+    pub fn publish(&self, change: Change<T>) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|subscriber| subscriber.send(change).is_ok());
+    }
+    pub fn subscribers(&self) -> usize {
+        self.subscribers.lock().unwrap().len()
+    }
+}
+
+impl<T: Copy + Send> Default for Feed<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//Every cell that differs between two grids, in a's bounds, as changes taking a to b
+pub fn diff<T, A, B>(a: &A, b: &B) -> Result<Vec<Change<T>>, String>
+where
+    T: Copy + PartialEq,
+    A: Grid<T> + ?Sized,
+    B: Grid<T> + ?Sized,
+{
+    let (a, b) = (a.snapshot(), b.snapshot());
+    if a.bounds() != b.bounds() {
+        return Err(format!(
+            "Can't diff grids with different bounds: {:?} and {:?}",
+            a.bounds(),
+            b.bounds()
+        ));
+    }
+    Ok(a.iter()
+        .zip(b.iter())
+        .filter(|((_, old), (_, new))| old != new)
+        .map(|((p, old), (_, new))| Change { p, old, new })
+        .collect())
+}
+
+//Replays changes in order, e.g. a diff or a recording, onto a grid
+pub fn apply<T, G, I>(grid: &mut G, changes: I)
+where
+    T: Copy,
+    G: GridMut<T> + ?Sized,
+    I: IntoIterator<Item = Change<T>>,
+{
+    for Change { p, new, .. } in changes {
+        grid.set(p, new);
+    }
+}
@@ -1,8 +1,12 @@
 use super::super::*;
 use super::*;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
 
 pub mod types {
-    pub use super::{Grid, PathResult, StepResult, ThreadedGrid};
+    pub use super::{
+        BuildGrid, Cells, Grid, GridMut, Neighbors, PathResult, StepResult, ThreadedGrid,
+    };
 }
 
 pub enum StepResult<T> {
@@ -15,23 +19,372 @@ pub enum PathResult {
     Stuck(Point),
     Died(Point),
 }
+//Reading only. Writes come from GridMut for grids with a single owner, or ThreadedGrid for grids that can be shared.
 pub trait Grid<T: Copy> {
     fn get(&self, p: Point) -> T;
     fn get_neighborhood(&self, p: Point) -> Neighborhood<T>;
+    //Point + Offset, according to this grid's topology
+    fn offset(&self, p: Point, o: Offset) -> Point;
+    fn step<D: Direction>(&self, pt: Point, dir: D) -> Point {
+        self.offset(pt, dir.offset())
+    }
+    fn rand(&self) -> Point;
+    //Shortest offset taking p1 to p2, according to this grid's topology
+    fn between(&self, p1: Point, p2: Point) -> Offset;
+    //Per-axis distance between two points
+    fn delta(&self, p1: Point, p2: Point) -> (usize, usize) {
+        self.between(p1, p2).delta()
+    }
+    fn distance(&self, p1: Point, p2: Point) -> f64 {
+        Metric::Euclidean.measure(self.delta(p1, p2))
+    }
+    fn metric_distance(&self, p1: Point, p2: Point, metric: Metric) -> f64 {
+        metric.measure(self.delta(p1, p2))
+    }
+    //Everything that can be iterated over. For unbounded grids that's just what's been written to.
+    fn bounds(&self) -> Rect;
+    //Which of the eight squares around p match f, from one read of the neighborhood
+    fn mask<F>(&self, p: Point, f: F) -> Mask
+    where
+        F: Fn(T) -> bool,
+    {
+        Mask::of(&self.get_neighborhood(p), f)
+    }
+    //Each square the stencil reaches from p, in the stencil's order, resolved through this grid's topology.
+    //Cheaper than get_neighborhood when only some of the nine matter, and not limited to them.
+    fn neighbors<'a>(&'a self, p: Point, stencil: &'a Stencil) -> Neighbors<'a, T, Self> {
+        Neighbors {
+            grid: self,
+            center: p,
+            offsets: stencil.offsets().iter(),
+            phantom: PhantomData,
+        }
+    }
+    //A borrowed window onto rect, addressed from its own top left corner
+    fn view(&self, rect: Rect) -> View<'_, T, Self> {
+        View::new(self, rect)
+    }
+    fn iter(&self) -> Cells<'_, T, Self> {
+        self.iter_rect(self.bounds())
+    }
+    fn iter_rect(&self, rect: Rect) -> Cells<'_, T, Self> {
+        Cells {
+            grid: self,
+            points: rect.points(),
+            phantom: PhantomData,
+        }
+    }
+    fn fold<A, F>(&self, init: A, f: F) -> A
+    where
+        F: FnMut(A, (Point, T)) -> A,
+    {
+        self.iter().fold(init, f)
+    }
+    fn count_where<F>(&self, f: F) -> usize
+    where
+        F: Fn(T) -> bool,
+    {
+        self.iter().filter(|(_, t)| f(*t)).count()
+    }
+    //Results are in local coordinates, with the origin at the corner of bounds()
+    fn map<A, F>(&self, f: F) -> WrappedGrid<A>
+    where
+        A: Copy,
+        F: Fn(T) -> A,
+    {
+        let bounds = self.bounds();
+        if bounds.area() == 0 {
+            return WrappedGrid::empty(bounds.width, bounds.height);
+        }
+        let mut mapped = WrappedGrid::new(bounds.width, bounds.height, f(self.get(bounds.origin)));
+        for (p, t) in self.iter_rect(bounds) {
+            mapped.set(bounds.local(p), f(t));
+        }
+        mapped
+    }
+    //A copy of the whole grid as of a single moment. Concurrent grids should override this to hold all their locks at once.
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.map(|t| t)
+    }
+}
+
+pub trait GridMut<T: Copy>: Grid<T> {
     fn set(&mut self, p: Point, value: T);
     fn update<F>(&mut self, p: Point, update: F)
     where
-        F: Fn(T) -> T;
-    fn step<D: Direction>(&self, pt: Point, dir: D) -> Point;
-    fn rand(&self) -> Point;
-    fn distance(&self, p1: Point, p2: Point) -> f64;
+        F: Fn(T) -> T,
+    {
+        let updated = update(self.get(p));
+        self.set(p, updated);
+    }
+}
+
+pub struct Cells<'a, T, G: ?Sized> {
+    grid: &'a G,
+    points: RectPoints,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: Copy, G: Grid<T> + ?Sized> Iterator for Cells<'a, T, G> {
+    type Item = (Point, T);
+    fn next(&mut self) -> Option<(Point, T)> {
+        let p = self.points.next()?;
+        Some((p, self.grid.get(p)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.points.size_hint()
+    }
+}
+
+pub struct Neighbors<'a, T, G: ?Sized> {
+    grid: &'a G,
+    center: Point,
+    offsets: std::slice::Iter<'a, Offset>,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: Copy, G: Grid<T> + ?Sized> Iterator for Neighbors<'a, T, G> {
+    type Item = (Point, T);
+    fn next(&mut self) -> Option<(Point, T)> {
+        let p = self.grid.offset(self.center, *self.offsets.next()?);
+        Some((p, self.grid.get(p)))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.offsets.size_hint()
+    }
 }
 
 pub trait ThreadedGrid<T: Copy>: Grid<T> {
+    //The one primitive: atomically replace the square with whatever f returns, if anything. Returns the old value.
+    //f may be called more than once if there's contention, so it shouldn't have side effects.
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>;
+    fn set(&self, p: Point, value: T) {
+        self.swap(p, value);
+    }
+    fn update<F>(&self, p: Point, update: F)
+    where
+        F: Fn(T) -> T,
+    {
+        self.fetch_update(p, |t| Some(update(t)));
+    }
+    fn swap(&self, p: Point, value: T) -> T {
+        self.fetch_update(p, |_| Some(value))
+    }
+    //Ok with the old value if it was current and got replaced, otherwise Err with what was there instead
+    fn compare_exchange(&self, p: Point, current: T, new: T) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        let previous = self.fetch_update(p, |t| (t == current).then_some(new));
+        if previous == current {
+            Ok(previous)
+        } else {
+            Err(previous)
+        }
+    }
     fn set_if<F>(&self, p: Point, f: F, value: T) -> bool
     where
-        F: Fn(T) -> bool;
+        F: Fn(T) -> bool,
+    {
+        f(self.fetch_update(p, |t| f(t).then_some(value)))
+    }
+    //All-or-nothing over any set of points: f gets their values in the same order as points, and nothing is
+    //written back unless it returns true. Nobody else can write to any of them in between.
+    //A point listed twice gets two cells, and the later one wins.
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool;
+    //Nothing is held between reading the neighborhood and acting on it, so a Change can overwrite someone else's writes.
+    //Grids with cheap transactions override this with locked_path.
     fn path<F>(&self, p: Point, step: &mut F) -> PathResult
     where
-        F: FnMut(Neighborhood<T>) -> StepResult<T>;
+        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+    {
+        let mut p = p;
+        loop {
+            match step(self.get_neighborhood(p)) {
+                StepResult::Step(Some(dir)) => p = self.step(p, dir),
+                StepResult::Step(None) => (),
+                StepResult::Stick(t) => {
+                    self.set(p, t);
+                    return PathResult::Stuck(p);
+                }
+                StepResult::Die => return PathResult::Died(p),
+                StepResult::Change(n) => {
+                    let points = Neighborhood::around(p).to_array();
+                    let values: Vec<T> = n.into_iter().collect();
+                    self.transact(&points, |cells| {
+                        cells.copy_from_slice(&values);
+                        true
+                    });
+                }
+            }
+        }
+    }
+    //The units of locking - workers that stick to separate regions never contend
+    fn regions(&self) -> Vec<Rect>;
+    //Lock contention counting, for grids with region locks to count. Switching it on starts the counts over.
+    fn set_instrumented(&self, _on: bool) {}
+    fn instrumented(&self) -> bool {
+        false
+    }
+    //One entry per region, in the same order as regions()
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        Vec::new()
+    }
+    fn par_for_each<F>(&self, num_threads: usize, f: F)
+    where
+        Self: Sync,
+        F: Fn(Point, T) + Sync,
+    {
+        self.par_fold(num_threads, (), |_, (p, t)| f(p, t), |_, _| ());
+    }
+    //Each thread folds its share of the regions from init, then the per-thread results are reduced in thread order
+    fn par_fold<A, F, R>(&self, num_threads: usize, init: A, fold: F, reduce: R) -> A
+    where
+        Self: Sync,
+        A: Clone + Send,
+        F: Fn(A, (Point, T)) -> A + Sync,
+        R: Fn(A, A) -> A,
+    {
+        let regions = self.regions();
+        let (regions, fold) = (&regions, &fold);
+        let results = crossbeam::scope(|scope| {
+            let handles: Vec<_> = (0..num_threads)
+                .map(|i| {
+                    let init = init.clone();
+                    scope.spawn(move |_| {
+                        regions
+                            .iter()
+                            .skip(i)
+                            .step_by(num_threads)
+                            .fold(init, |acc, region| self.iter_rect(*region).fold(acc, fold))
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect::<Vec<A>>()
+        })
+        .unwrap();
+        results.into_iter().fold(init, reduce)
+    }
+}
+
+//Lets burgs be generic over which grid backs them
+pub trait BuildGrid<T>: Sized {
+    fn build(width: usize, height: usize, default: T) -> Self;
+    //Grids ignore whichever of these they have no use for, e.g. the partition on grids without region locks
+    fn build_with(width: usize, height: usize, default: T, _: Partition, _: Layout) -> Self {
+        Self::build(width, height, default)
+    }
+}
+
+//transact for the grids that lock by region. cells is the (lock, index within it) of each point.
+pub(super) fn transact_regions<K, T, L, W, F>(cells: &[(K, usize)], lock: L, f: F) -> bool
+where
+    K: Ord + Copy,
+    T: Copy,
+    L: Fn(K) -> W,
+    W: DerefMut<Target = Vec<T>>,
+    F: FnOnce(&mut [T]) -> bool,
+{
+    //Always lock in key order, so two transactions can never each be holding what the other is waiting on
+    let mut keys: Vec<K> = cells.iter().map(|(key, _)| *key).collect();
+    keys.sort();
+    keys.dedup();
+    let mut guards: Vec<_> = keys.iter().map(|key| lock(*key)).collect();
+    let slots: Vec<(usize, usize)> = cells
+        .iter()
+        .map(|(key, i)| (keys.binary_search(key).unwrap(), *i))
+        .collect();
+    let mut values: Vec<T> = slots.iter().map(|&(g, i)| guards[g][i]).collect();
+    let commit = f(&mut values);
+    if commit {
+        for (&(g, i), value) in slots.iter().zip(values) {
+            guards[g][i] = value;
+        }
+    }
+    commit
+}
+
+//Read a whole neighborhood taking each region's lock once, in key order like transact_regions, rather than once per
+//square. It also means the neighborhood is all from one moment.
+pub(super) fn read_regions<K, T, L, R>(cells: Neighborhood<(K, usize)>, lock: L) -> Neighborhood<T>
+where
+    K: Ord + Copy,
+    T: Copy,
+    L: Fn(K) -> R,
+    R: Deref<Target = Vec<T>>,
+{
+    //On the stack, as this is every step of every walker
+    let mut keys = cells.map(|(key, _)| key).to_array();
+    let keys = sorted_unique(&mut keys);
+    let mut guards: [Option<R>; 9] = Default::default();
+    for (guard, key) in guards.iter_mut().zip(keys.iter()) {
+        *guard = Some(lock(*key));
+    }
+    cells.map(|(key, i)| guards[keys.binary_search(&key).unwrap()].as_ref().unwrap()[i])
+}
+
+//Sorts keys and moves the distinct ones to the front, returning just those
+pub(super) fn sorted_unique<K: Ord + Copy>(keys: &mut [K]) -> &[K] {
+    keys.sort_unstable();
+    let mut unique = 0;
+    for i in 0..keys.len() {
+        if unique == 0 || keys[i] != keys[unique - 1] {
+            keys[unique] = keys[i];
+            unique += 1;
+        }
+    }
+    &keys[..unique]
+}
+
+//path with each step as one transaction: the neighborhood is read and acted on under the same locks, so nothing
+//can change between a walker looking and it sticking
+pub(super) fn locked_path<T, G, F>(grid: &G, start: Point, step: &mut F) -> PathResult
+where
+    T: Copy,
+    G: ThreadedGrid<T> + ?Sized,
+    F: FnMut(Neighborhood<T>) -> StepResult<T>,
+{
+    let mut p = start;
+    loop {
+        let points = Neighborhood::around(p).to_array();
+        let (mut dir, mut done) = (None, None);
+        grid.transact(&points, |cells| {
+            match step(cells.iter().copied().collect()) {
+                StepResult::Step(d) => {
+                    dir = d;
+                    false
+                }
+                StepResult::Die => {
+                    done = Some(PathResult::Died(p));
+                    false
+                }
+                StepResult::Stick(t) => {
+                    //The centre comes first
+                    cells[0] = t;
+                    done = Some(PathResult::Stuck(p));
+                    true
+                }
+                StepResult::Change(n) => {
+                    cells
+                        .iter_mut()
+                        .zip(n)
+                        .for_each(|(cell, value)| *cell = value);
+                    true
+                }
+            }
+        });
+        if let Some(result) = done {
+            return result;
+        }
+        if let Some(d) = dir {
+            p = grid.step(p, d);
+        }
+    }
 }
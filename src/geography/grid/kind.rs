@@ -0,0 +1,28 @@
+use clap::ValueEnum;
+use std::fmt::{Display, Formatter, Result};
+
+pub mod types {
+    pub use super::GridKind;
+}
+
+//The backing grids a burg can be run on, for comparing them on the same scenario
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GridKind {
+    Wrapped,
+    Rw,
+    //The same as Rw
+    Regional,
+    Atomic,
+}
+
+impl Display for GridKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let s = match self {
+            GridKind::Wrapped => "wrapped",
+            GridKind::Rw => "rw",
+            GridKind::Regional => "regional",
+            GridKind::Atomic => "atomic",
+        };
+        write!(f, "{}", s)
+    }
+}
@@ -0,0 +1,3 @@
+mod graphics;
+mod layered_grid;
+pub use self::layered_grid::{Layer, LayerKind, LayerSet, LayeredGrid};
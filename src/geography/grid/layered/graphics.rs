@@ -0,0 +1,25 @@
+use super::layered_grid::*;
+use crate::utils::draw_utils::Draw;
+use cairo::Context;
+use std::any::Any;
+use std::sync::atomic::Ordering;
+
+impl<K: 'static> Draw for LayeredGrid<K> {
+    fn draw(&self, context: &Context) {
+        for entry in self.layers.iter() {
+            if let Some(draw) = entry.draw {
+                if entry.visible.load(Ordering::Relaxed) {
+                    draw(entry.grid.as_ref(), context);
+                }
+            }
+        }
+    }
+}
+
+pub(super) fn draw_layer<K, T: Copy>(grid: &dyn Any, context: &Context)
+where
+    K: LayerKind<T>,
+    K::Layer: Draw,
+{
+    grid.downcast_ref::<K::Layer>().unwrap().draw(context);
+}
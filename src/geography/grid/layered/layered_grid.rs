@@ -0,0 +1,206 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+//Several grids of different types over the same squares - terrain, occupants, scent fields - each its own grid
+//of kind K with its own locks, so writing to one layer never blocks readers of another.
+pub struct LayeredGrid<K = RwGrid<()>> {
+    pub(super) topology: WrappedGrid<()>,
+    partition: Partition,
+    layout: Layout,
+    pub(super) layers: Vec<LayerEntry>,
+    phantom: PhantomData<fn() -> K>,
+}
+
+//Which grid a LayeredGrid keeps a layer of T in. Named by that grid over (), so burgs that are generic over their
+//backing grid can be handed one the same way, e.g. LayeredGrid<AtomicGrid<()>>. A kind only takes the Ts its grid
+//can, so AtomicGrid layers have to be Eq.
+pub trait LayerKind<T: Copy>: 'static {
+    type Layer: ThreadedGrid<T> + BuildGrid<T> + Send + Sync + 'static;
+}
+
+impl<T: Copy + Send + Sync + 'static> LayerKind<T> for LockedGrid<WrappedGrid<()>> {
+    type Layer = LockedGrid<WrappedGrid<T>>;
+}
+impl<T: Copy + Send + Sync + 'static> LayerKind<T> for RwGrid<()> {
+    type Layer = RwGrid<T>;
+}
+impl<T: Copy + Eq + Send + Sync + 'static> LayerKind<T> for AtomicGrid<()> {
+    type Layer = AtomicGrid<T>;
+}
+
+pub(super) struct LayerEntry {
+    name: String,
+    pub(super) grid: Box<dyn Any + Send + Sync>,
+    //Only layers with something to show get one of these
+    pub(super) draw: Option<fn(&dyn Any, &cairo::Context)>,
+    pub(super) visible: AtomicBool,
+}
+
+//A typed handle to one layer, from LayeredGrid::add
+pub struct Layer<T> {
+    index: usize,
+    phantom: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for Layer<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for Layer<T> {}
+
+//Layers in RwGrids, unless asked for something else with build_with
+impl LayeredGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::with_partition(width, height, Partition::default(), Layout::default())
+    }
+    pub fn with_partition(
+        width: usize,
+        height: usize,
+        partition: Partition,
+        layout: Layout,
+    ) -> Self {
+        Self::build_with(width, height, partition, layout)
+    }
+}
+
+impl<K: 'static> LayeredGrid<K> {
+    pub fn build_with(width: usize, height: usize, partition: Partition, layout: Layout) -> Self {
+        LayeredGrid {
+            topology: WrappedGrid::with_layout(width, height, (), layout),
+            partition,
+            layout,
+            layers: Vec::new(),
+            phantom: PhantomData,
+        }
+    }
+    pub fn add<T>(&mut self, name: &str, default: T) -> Layer<T>
+    where
+        T: Copy + Send + Sync + 'static,
+        K: LayerKind<T>,
+    {
+        self.push(name, default, None)
+    }
+    //A layer that's drawn along with the grid, in the order added
+    pub fn add_drawn<T>(&mut self, name: &str, default: T) -> Layer<T>
+    where
+        T: Colored + Copy + Send + Sync + 'static,
+        K: LayerKind<T>,
+        K::Layer: Draw,
+    {
+        self.push(name, default, Some(graphics::draw_layer::<K, T>))
+    }
+    //Each layer is a whole ThreadedGrid in its own right
+    pub fn layer<T: Copy>(&self, layer: Layer<T>) -> &K::Layer
+    where
+        K: LayerKind<T>,
+    {
+        self.layers[layer.index].grid.downcast_ref().unwrap()
+    }
+    //Looks a layer up by name, if there's one with that name and type
+    pub fn find<T: Copy>(&self, name: &str) -> Option<Layer<T>>
+    where
+        K: LayerKind<T>,
+    {
+        self.layers
+            .iter()
+            .position(|entry| entry.name == name && entry.grid.is::<K::Layer>())
+            .map(|index| Layer {
+                index,
+                phantom: PhantomData,
+            })
+    }
+    pub fn names(&self) -> Vec<&str> {
+        self.layers
+            .iter()
+            .map(|entry| entry.name.as_str())
+            .collect()
+    }
+    pub fn show(&self, name: &str, visible: bool) {
+        for entry in self.layers.iter().filter(|entry| entry.name == name) {
+            entry.visible.store(visible, Ordering::Relaxed);
+        }
+    }
+    //One layer or a tuple of them, e.g. grid.get(p, (food, home))
+    pub fn get<L: LayerSet<K>>(&self, p: Point, layers: L) -> L::Value {
+        layers.get(self, p)
+    }
+    pub fn get_neighborhood<L: LayerSet<K>>(&self, p: Point, layers: L) -> Neighborhood<L::Value> {
+        layers.get_neighborhood(self, p)
+    }
+    //Every layer shares this, so step, offset, between and bounds can all be asked of it
+    pub fn topology(&self) -> &WrappedGrid<()> {
+        &self.topology
+    }
+    fn push<T>(
+        &mut self,
+        name: &str,
+        default: T,
+        draw: Option<fn(&dyn Any, &cairo::Context)>,
+    ) -> Layer<T>
+    where
+        T: Copy + Send + Sync + 'static,
+        K: LayerKind<T>,
+    {
+        let Rect { width, height, .. } = self.topology.bounds();
+        let grid = K::Layer::build_with(width, height, default, self.partition, self.layout);
+        self.layers.push(LayerEntry {
+            name: name.to_string(),
+            grid: Box::new(grid),
+            draw,
+            visible: AtomicBool::new(true),
+        });
+        Layer {
+            index: self.layers.len() - 1,
+            phantom: PhantomData,
+        }
+    }
+}
+
+//Whatever can be read from a LayeredGrid of kind K in one go: a single layer, or a tuple of them
+pub trait LayerSet<K>: Copy {
+    type Value: Copy;
+    fn get(&self, grid: &LayeredGrid<K>, p: Point) -> Self::Value;
+    fn get_neighborhood(&self, grid: &LayeredGrid<K>, p: Point) -> Neighborhood<Self::Value>;
+}
+
+impl<T: Copy + 'static, K: LayerKind<T>> LayerSet<K> for Layer<T> {
+    type Value = T;
+    fn get(&self, grid: &LayeredGrid<K>, p: Point) -> T {
+        grid.layer(*self).get(p)
+    }
+    fn get_neighborhood(&self, grid: &LayeredGrid<K>, p: Point) -> Neighborhood<T> {
+        grid.layer(*self).get_neighborhood(p)
+    }
+}
+
+macro_rules! layer_tuple {
+    ($($layer:ident: $t:ident),+) => {
+        impl<K: 'static, $($t: Copy + 'static),+> LayerSet<K> for ($(Layer<$t>,)+)
+        where
+            $(K: LayerKind<$t>),+
+        {
+            type Value = ($($t,)+);
+            fn get(&self, grid: &LayeredGrid<K>, p: Point) -> Self::Value {
+                let ($($layer,)+) = *self;
+                ($(grid.layer($layer).get(p),)+)
+            }
+            //Each layer's neighborhood is read under its own locks, then zipped together
+            fn get_neighborhood(&self, grid: &LayeredGrid<K>, p: Point) -> Neighborhood<Self::Value> {
+                let ($($layer,)+) = *self;
+                let ($(mut $layer,)+) = ($(grid.layer($layer).get_neighborhood(p).into_iter(),)+);
+                (0..9).map(|_| ($($layer.next().unwrap(),)+)).collect()
+            }
+        }
+    };
+}
+
+layer_tuple!(a: A, b: B);
+layer_tuple!(a: A, b: B, c: C);
+layer_tuple!(a: A, b: B, c: C, d: D);
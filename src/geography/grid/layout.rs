@@ -0,0 +1,129 @@
+use clap::ValueEnum;
+use std::fmt::{Display, Formatter, Result};
+
+pub mod types {
+    pub use super::Layout;
+}
+
+//Tiles are TILE_SIZE squares on a side. Perf-tuning const - 8x8 of anything small fits in a few cache lines.
+const TILE_SIZE: usize = 8;
+const TILE_AREA: usize = TILE_SIZE * TILE_SIZE;
+//Z-order blocks are at most this many squares on a side. Has to be a power of two.
+const Z_BLOCK: usize = 64;
+
+//Order squares are stored in memory. Row-major keeps rows together, which is great for scanning and bad for
+//anything that wanders vertically; the other two keep squares that are close in 2D close in memory.
+#[derive(ValueEnum, Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Layout {
+    #[default]
+    RowMajor,
+    //TILE_SIZE squares on a side, row-major inside each tile and tiles row-major across the grid
+    Tiled,
+    //Morton order - interleaves the bits of x and y - inside square blocks, with the blocks row-major across the grid.
+    //Blocks are Z_BLOCK on a side, or the biggest power of two that fits the shorter side, so padding only ever fills
+    //out the last row and column of blocks.
+    ZOrder,
+}
+
+impl Layout {
+    //Storage needed for a width x height area. Tiled and ZOrder pad out to whole tiles and blocks.
+    pub fn len(&self, width: usize, height: usize) -> usize {
+        if width == 0 || height == 0 {
+            return 0;
+        }
+        match self {
+            Layout::RowMajor => width * height,
+            Layout::Tiled => width.div_ceil(TILE_SIZE) * height.div_ceil(TILE_SIZE) * TILE_AREA,
+            Layout::ZOrder => {
+                let shift = z_shift(width, height);
+                (width.div_ceil(1 << shift) * height.div_ceil(1 << shift)) << (2 * shift)
+            }
+        }
+    }
+    //x and y have to be in bounds already
+    #[inline(always)]
+    pub fn index(&self, width: usize, height: usize, x: usize, y: usize) -> usize {
+        match self {
+            Layout::RowMajor => y * width + x,
+            Layout::Tiled => {
+                let tiles_per_row = width.div_ceil(TILE_SIZE);
+                let tile = (y / TILE_SIZE) * tiles_per_row + x / TILE_SIZE;
+                tile * TILE_AREA + (y % TILE_SIZE) * TILE_SIZE + x % TILE_SIZE
+            }
+            Layout::ZOrder => {
+                let shift = z_shift(width, height);
+                let mask = (1 << shift) - 1;
+                let block = (y >> shift) * width.div_ceil(1 << shift) + (x >> shift);
+                (block << (2 * shift)) | spread(x & mask) | (spread(y & mask) << 1)
+            }
+        }
+    }
+}
+
+//Z-order blocks are 2^z_shift squares on a side
+#[inline(always)]
+fn z_shift(width: usize, height: usize) -> usize {
+    usize::min(Z_BLOCK, usize::min(width, height)).ilog2() as usize
+}
+
+//Puts a zero between each bit, so 0b1011 becomes 0b1000101. Only the low 32 bits survive.
+#[inline(always)]
+fn spread(n: usize) -> usize {
+    let mut n = n as u64 & 0xFFFF_FFFF;
+    n = (n | (n << 16)) & 0x0000_FFFF_0000_FFFF;
+    n = (n | (n << 8)) & 0x00FF_00FF_00FF_00FF;
+    n = (n | (n << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    n = (n | (n << 2)) & 0x3333_3333_3333_3333;
+    n = (n | (n << 1)) & 0x5555_5555_5555_5555;
+    n as usize
+}
+
+impl Display for Layout {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let s = match self {
+            Layout::RowMajor => "row-major",
+            Layout::Tiled => "tiled",
+            Layout::ZOrder => "z-order",
+        };
+        //pad rather than write!, so it lines up in tables
+        f.pad(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn every_square_gets_its_own_slot() {
+        for layout in Layout::value_variants() {
+            for (width, height) in [(1, 1), (5, 3), (16, 1024), (100, 70), (130, 129)] {
+                let len = layout.len(width, height);
+                let mut seen = HashSet::new();
+                for y in 0..height {
+                    for x in 0..width {
+                        let i = layout.index(width, height, x, y);
+                        assert!(
+                            i < len,
+                            "{layout} {width}x{height} put ({x},{y}) at {i} of {len}"
+                        );
+                        assert!(seen.insert(i), "{layout} {width}x{height} reused {i}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn z_order_padding() {
+        assert_eq!(Layout::ZOrder.len(1024, 16), 1024 * 16);
+        assert_eq!(Layout::ZOrder.len(1024, 1024), 1024 * 1024);
+        //Only the last row and column of 64x64 blocks are padded out
+        assert_eq!(Layout::ZOrder.len(1000, 1000), 1024 * 1024);
+        assert_eq!(Layout::ZOrder.len(1025, 1025), 1088 * 1088);
+        //Squares next to each other in a block stay next to each other in memory
+        assert_eq!(Layout::ZOrder.index(128, 128, 1, 1), 3);
+        assert_eq!(Layout::ZOrder.index(128, 128, 64, 0), 64 * 64);
+    }
+}
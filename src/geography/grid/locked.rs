@@ -0,0 +1,3 @@
+mod graphics;
+mod locked_grid;
+pub use self::locked_grid::LockedGrid;
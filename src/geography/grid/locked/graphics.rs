@@ -0,0 +1,9 @@
+use super::locked_grid::*;
+use crate::utils::draw_utils::Draw;
+use cairo::Context;
+
+impl<G: Draw> Draw for LockedGrid<G> {
+    fn draw(&self, context: &Context) {
+        self.grid.read().unwrap().draw(context);
+    }
+}
@@ -0,0 +1,97 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::sync::RwLock;
+
+//Makes any single-owner grid shareable between threads by putting the whole thing behind one lock
+pub struct LockedGrid<G> {
+    pub(super) grid: RwLock<G>,
+}
+
+impl<T: Copy, G: Grid<T>> Grid<T> for LockedGrid<G> {
+    fn get(&self, p: Point) -> T {
+        self.grid.read().unwrap().get(p)
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        self.grid.read().unwrap().get_neighborhood(p)
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        self.grid.read().unwrap().offset(p, o)
+    }
+    fn rand(&self) -> Point {
+        self.grid.read().unwrap().rand()
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        self.grid.read().unwrap().between(p1, p2)
+    }
+    fn bounds(&self) -> Rect {
+        self.grid.read().unwrap().bounds()
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.grid.read().unwrap().snapshot()
+    }
+}
+
+impl<T: Copy, G: GridMut<T>> ThreadedGrid<T> for LockedGrid<G> {
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let mut grid = self.grid.write().unwrap();
+        let pre_existing = grid.get(p);
+        if let Some(value) = f(pre_existing) {
+            grid.set(p, value);
+        }
+        pre_existing
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let mut grid = self.grid.write().unwrap();
+        let mut values: Vec<T> = points.iter().map(|p| grid.get(*p)).collect();
+        let commit = f(&mut values);
+        if commit {
+            for (p, value) in points.iter().zip(values) {
+                grid.set(*p, value);
+            }
+        }
+        commit
+    }
+    //There's only the one lock
+    fn regions(&self) -> Vec<Rect> {
+        vec![self.bounds()]
+    }
+}
+
+impl<G> LockedGrid<G> {
+    pub fn new(grid: G) -> Self {
+        LockedGrid {
+            grid: RwLock::new(grid),
+        }
+    }
+    //Owning the lock outright means skipping it
+    pub fn get_mut(&mut self) -> &mut G {
+        self.grid.get_mut().unwrap()
+    }
+    pub fn into_inner(self) -> G {
+        self.grid.into_inner().unwrap()
+    }
+}
+
+impl<T, G: BuildGrid<T>> BuildGrid<T> for LockedGrid<G> {
+    fn build(width: usize, height: usize, default: T) -> Self {
+        Self::new(G::build(width, height, default))
+    }
+    fn build_with(
+        width: usize,
+        height: usize,
+        default: T,
+        partition: Partition,
+        layout: Layout,
+    ) -> Self {
+        Self::new(G::build_with(width, height, default, partition, layout))
+    }
+}
@@ -0,0 +1,216 @@
+//Model-checked interleavings of the region-locked grids. Loom replaces the locks and atomics (see crate::sync) and
+//runs every closure below under each schedule it can reach, failing on a broken assertion or a deadlock:
+//    RUSTFLAGS="--cfg loom" cargo test --release loom_tests
+use crate::geography::*;
+use loom::sync::Arc;
+use loom::thread;
+
+//4x4 cells in four 2x2 regions, so a neighborhood around (1, 1) or (2, 2) touches every lock
+fn rw() -> RwGrid<u32> {
+    RwGrid::with_partition(4, 4, 0, Partition::new(2, 2))
+}
+
+fn regional() -> RegionalGrid<u32> {
+    RegionalGrid::with_partition(4, 4, 0, Partition::new(2, 2))
+}
+
+//Runs each of the closures on its own thread against one shared grid, then checks the grid once they are all done
+fn race<G, F>(build: fn() -> G, threads: Vec<F>, check: fn(&G))
+where
+    G: ThreadedGrid<u32> + Send + Sync + 'static,
+    F: Fn(&G) + Send + Sync + Clone + 'static,
+{
+    loom::model(move || {
+        let grid = Arc::new(build());
+        let handles: Vec<_> = threads
+            .iter()
+            .cloned()
+            .map(|f| {
+                let grid = grid.clone();
+                thread::spawn(move || f(&grid))
+            })
+            .collect();
+        handles.into_iter().for_each(|h| h.join().unwrap());
+        check(&grid);
+    });
+}
+
+fn set_if_has_one_winner<G: ThreadedGrid<u32> + Send + Sync + 'static>(build: fn() -> G) {
+    let claim = |id: u32| {
+        move |g: &G| {
+            if g.set_if(Point(1, 1), |v| v == 0, id) {
+                g.update(Point(3, 3), |v| v + 1);
+            }
+        }
+    };
+    race(build, vec![claim(1), claim(2)], |g| {
+        assert_ne!(g.get(Point(1, 1)), 0);
+        assert_eq!(g.get(Point(3, 3)), 1);
+    });
+}
+
+fn updates_are_not_lost<G: ThreadedGrid<u32> + Send + Sync + 'static>(build: fn() -> G) {
+    let bump = |g: &G| {
+        g.update(Point(0, 0), |v| v + 1);
+    };
+    race(build, vec![bump, bump], |g| {
+        assert_eq!(g.get(Point(0, 0)), 2)
+    });
+}
+
+//Every cell a reader sees is one a writer finished writing, even with the neighborhood split across regions
+fn neighborhoods_are_not_torn<G: ThreadedGrid<u32> + Send + Sync + 'static>(build: fn() -> G) {
+    loom::model(move || {
+        let grid = Arc::new(build());
+        let writer = {
+            let grid = grid.clone();
+            thread::spawn(move || {
+                grid.set(Point(1, 1), 7);
+                grid.set(Point(2, 2), 7);
+            })
+        };
+        let n = grid.get_neighborhood(Point(1, 1));
+        assert!([n.c, n.se].iter().all(|v| *v == 0 || *v == 7));
+        writer.join().unwrap();
+        assert_eq!(grid.get_neighborhood(Point(1, 1)).se, 7);
+    });
+}
+
+//Transactions over the same two regions taken in opposite orders must neither deadlock nor interleave
+fn crossed_transactions_finish<G: ThreadedGrid<u32> + Send + Sync + 'static>(build: fn() -> G) {
+    let transfer = |from: Point, to: Point| {
+        move |g: &G| {
+            g.transact(&[from, to], |cells| {
+                cells[0] += 1;
+                cells[1] += 1;
+                true
+            });
+        }
+    };
+    race(
+        build,
+        vec![
+            transfer(Point(0, 0), Point(3, 3)),
+            transfer(Point(3, 3), Point(0, 0)),
+        ],
+        |g| {
+            assert_eq!((g.get(Point(0, 0)), g.get(Point(3, 3))), (2, 2));
+        },
+    );
+}
+
+//Two walkers changing the same overlapping neighborhood: each Change is read and written under the same locks, so
+//neither increment is lost
+fn locked_paths_do_not_lose_changes<G: ThreadedGrid<u32> + Send + Sync + 'static>(
+    build: fn() -> G,
+) {
+    let walker = |start: Point| {
+        move |g: &G| {
+            let mut changed = false;
+            g.path(start, &mut |mut n: Neighborhood<u32>| {
+                if changed {
+                    return StepResult::Die;
+                }
+                changed = true;
+                n.se += 1;
+                StepResult::Change(n)
+            });
+        }
+    };
+    race(build, vec![walker(Point(1, 1)), walker(Point(1, 1))], |g| {
+        assert_eq!(g.get(Point(2, 2)), 2)
+    });
+}
+
+//A walker only sticks next to a square it saw filled, and a concurrent writer can't empty it in between
+fn locked_paths_stick_on_what_they_saw<G: ThreadedGrid<u32> + Send + Sync + 'static>(
+    build: fn() -> G,
+) {
+    loom::model(move || {
+        let grid = Arc::new(build());
+        grid.set(Point(2, 2), 1);
+        let eraser = {
+            let grid = grid.clone();
+            thread::spawn(move || {
+                grid.transact(&[Point(2, 2), Point(1, 1)], |cells| {
+                    cells[1] == 0 && {
+                        cells[0] = 0;
+                        true
+                    }
+                })
+            })
+        };
+        let result = grid.path(Point(1, 1), &mut |n: Neighborhood<u32>| {
+            if n.se == 1 {
+                StepResult::Stick(2)
+            } else {
+                StepResult::Die
+            }
+        });
+        let erased = eraser.join().unwrap();
+        match result {
+            PathResult::Stuck(_) => assert!(!erased && grid.get(Point(2, 2)) == 1),
+            PathResult::Died(_) => assert!(erased),
+        }
+    });
+}
+
+#[test]
+fn rw_set_if() {
+    set_if_has_one_winner(rw);
+}
+
+#[test]
+fn regional_set_if() {
+    set_if_has_one_winner(regional);
+}
+
+#[test]
+fn rw_updates() {
+    updates_are_not_lost(rw);
+}
+
+#[test]
+fn regional_updates() {
+    updates_are_not_lost(regional);
+}
+
+#[test]
+fn rw_neighborhoods() {
+    neighborhoods_are_not_torn(rw);
+}
+
+#[test]
+fn regional_neighborhoods() {
+    neighborhoods_are_not_torn(regional);
+}
+
+#[test]
+fn rw_transactions() {
+    crossed_transactions_finish(rw);
+}
+
+#[test]
+fn regional_transactions() {
+    crossed_transactions_finish(regional);
+}
+
+#[test]
+fn rw_locked_path_changes() {
+    locked_paths_do_not_lose_changes(rw);
+}
+
+#[test]
+fn regional_locked_path_changes() {
+    locked_paths_do_not_lose_changes(regional);
+}
+
+#[test]
+fn rw_locked_path_sticks() {
+    locked_paths_stick_on_what_they_saw(rw);
+}
+
+#[test]
+fn regional_locked_path_sticks() {
+    locked_paths_stick_on_what_they_saw(regional);
+}
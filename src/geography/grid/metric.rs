@@ -0,0 +1,64 @@
+use super::*;
+use clap::ValueEnum;
+use std::fmt::{Display, Formatter, Result};
+
+pub mod types {
+    pub use super::Metric;
+}
+
+//Metrics only ever see the per-axis deltas, so whoever owns the topology (usually a grid) decides what those are
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Euclidean,
+    Manhattan,
+    Chebyshev,
+    Octile,
+}
+
+impl Metric {
+    pub fn measure(&self, (dx, dy): (usize, usize)) -> f64 {
+        let (dx, dy) = (dx as f64, dy as f64);
+        match self {
+            Metric::Euclidean => (dx * dx + dy * dy).sqrt(),
+            Metric::Manhattan => dx + dy,
+            Metric::Chebyshev => f64::max(dx, dy),
+            Metric::Octile => {
+                f64::max(dx, dy) + (std::f64::consts::SQRT_2 - 1.0) * f64::min(dx, dy)
+            }
+        }
+    }
+    //Ignores wrapping entirely - only use this when the edges of the world are really edges
+    pub fn flat(&self, p1: Point, p2: Point) -> f64 {
+        self.measure((p1.0.abs_diff(p2.0), p1.1.abs_diff(p2.1)))
+    }
+}
+
+//Shortest signed step from a to b along an axis of the given length that wraps around
+#[inline(always)]
+pub fn wrapped_offset(a: usize, b: usize, length: usize) -> isize {
+    let d = (b as isize - a as isize).rem_euclid(length as isize);
+    if d * 2 > length as isize {
+        d - length as isize
+    } else {
+        d
+    }
+}
+
+//Shortest distance between a and b along an axis of the given length that wraps around
+#[inline(always)]
+pub fn wrapped_delta(a: usize, b: usize, length: usize) -> usize {
+    let d = a.abs_diff(b) % length;
+    usize::min(d, length - d)
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let s = match self {
+            Metric::Euclidean => "euclidean",
+            Metric::Manhattan => "manhattan",
+            Metric::Chebyshev => "chebyshev",
+            Metric::Octile => "octile",
+        };
+        write!(f, "{}", s)
+    }
+}
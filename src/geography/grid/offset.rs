@@ -0,0 +1,95 @@
+use super::*;
+use std::fmt::{Display, Formatter, Result};
+use std::ops::{Add, Mul, Neg, Sub};
+
+pub mod types {
+    pub use super::Offset;
+}
+
+//A signed step between two points. Adding one to a Point on its own just wraps around usize, like Neighborhood::around;
+//go through Grid::offset to have the grid's topology applied.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Offset(pub isize, pub isize);
+
+impl Offset {
+    pub const ZERO: Offset = Offset(0, 0);
+    #[inline(always)]
+    pub fn x(&self) -> isize {
+        self.0
+    }
+    #[inline(always)]
+    pub fn y(&self) -> isize {
+        self.1
+    }
+    pub fn scale(self, factor: isize) -> Self {
+        Self(self.0 * factor, self.1 * factor)
+    }
+    //y grows downwards, so turning right takes N (0, -1) to E (1, 0)
+    pub fn rotate_right(self) -> Self {
+        Self(-self.1, self.0)
+    }
+    pub fn rotate_left(self) -> Self {
+        Self(self.1, -self.0)
+    }
+    //Quarter turns to the right. Negative turns go left.
+    pub fn rotate(self, quarter_turns: isize) -> Self {
+        match quarter_turns.rem_euclid(4) {
+            0 => self,
+            1 => self.rotate_right(),
+            2 => -self,
+            _ => self.rotate_left(),
+        }
+    }
+    pub fn delta(&self) -> (usize, usize) {
+        (self.0.unsigned_abs(), self.1.unsigned_abs())
+    }
+    pub fn length(&self) -> f64 {
+        Metric::Euclidean.measure(self.delta())
+    }
+}
+
+impl Add for Offset {
+    type Output = Self;
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl Sub for Offset {
+    type Output = Self;
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl Neg for Offset {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Self(-self.0, -self.1)
+    }
+}
+
+impl Mul<isize> for Offset {
+    type Output = Self;
+    fn mul(self, factor: isize) -> Self {
+        self.scale(factor)
+    }
+}
+
+impl From<(i32, i32)> for Offset {
+    fn from((x, y): (i32, i32)) -> Self {
+        Self(x as isize, y as isize)
+    }
+}
+
+impl From<Offset> for (i32, i32) {
+    fn from(o: Offset) -> Self {
+        (o.0 as i32, o.1 as i32)
+    }
+}
+
+impl Display for Offset {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "({:+}, {:+})", self.0, self.1)
+    }
+}
@@ -0,0 +1,3 @@
+mod graphics;
+mod overview_grid;
+pub use self::overview_grid::OverviewGrid;
@@ -0,0 +1,34 @@
+use super::overview_grid::*;
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use cairo::Context;
+
+//At least a pixel a block: a level coarser than the window can show, drawing each block in its majority color. Grids
+//that already fit are drawn cell by cell as usual.
+impl<T: Copy, G: ThreadedGrid<T> + Draw> Draw for OverviewGrid<G, T> {
+    fn draw(&self, context: &Context) {
+        let Rect { width, height, .. } = self.bounds();
+        let size = usize::max(width, height);
+        let level = self.pyramid.level_for(color::scale(size, size) as usize);
+        if level == 0 {
+            return self.grid.draw(context);
+        }
+        let side = color::scale(1 << level, size);
+        for (Point(x, y), category) in self.overview(level).iter() {
+            let color = self.colors[category];
+            if color != color::BLACK {
+                context.set_color(color);
+                context.rectangle(
+                    color::scale(x << level, size),
+                    color::scale(y << level, size),
+                    side,
+                    side,
+                );
+                context.fill().unwrap();
+            }
+        }
+    }
+}
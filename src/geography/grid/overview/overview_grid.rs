@@ -0,0 +1,134 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+
+//A threaded grid that also keeps a Pyramid of its cells sorted into categories, each drawn in its own color, so
+//drawing a big world or counting what's in it never has to look at every cell. Only writes made through the wrapper
+//are seen.
+pub struct OverviewGrid<G, T> {
+    pub(super) grid: G,
+    pub(super) pyramid: Pyramid,
+    pub(super) colors: Vec<Color>,
+    categorize: Box<dyn Fn(T) -> usize + Send + Sync>,
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> Grid<T> for OverviewGrid<G, T> {
+    fn get(&self, p: Point) -> T {
+        self.grid.get(p)
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        self.grid.get_neighborhood(p)
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        self.grid.offset(p, o)
+    }
+    fn rand(&self) -> Point {
+        self.grid.rand()
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        self.grid.between(p1, p2)
+    }
+    fn bounds(&self) -> Rect {
+        self.grid.bounds()
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.grid.snapshot()
+    }
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> ThreadedGrid<T> for OverviewGrid<G, T> {
+    //A transaction, so the pyramid is written while the grid still holds the cell
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let mut pre_existing = None;
+        self.transact(&[p], |cells| {
+            pre_existing = Some(cells[0]);
+            match f(cells[0]) {
+                Some(value) => {
+                    cells[0] = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        pre_existing.unwrap()
+    }
+    //Writes to a cell reach the pyramid in the order the grid made them, because they're made under its locks. Points
+    //are wrapped beforehand, as the grid can't be called back into while it holds them.
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let points: Vec<Point> = points
+            .iter()
+            .map(|p| self.grid.offset(*p, Offset::ZERO))
+            .collect();
+        self.grid.transact(&points, |cells| {
+            let commit = f(cells);
+            if commit {
+                for (p, value) in points.iter().zip(cells.iter()) {
+                    self.pyramid.set(*p, (self.categorize)(*value));
+                }
+            }
+            commit
+        })
+    }
+    fn path<F>(&self, p: Point, step: &mut F) -> PathResult
+    where
+        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+    {
+        super::super::grid::locked_path(self, p, step)
+    }
+    fn regions(&self) -> Vec<Rect> {
+        self.grid.regions()
+    }
+    fn set_instrumented(&self, on: bool) {
+        self.grid.set_instrumented(on);
+    }
+    fn instrumented(&self) -> bool {
+        self.grid.instrumented()
+    }
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        self.grid.lock_stats()
+    }
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> OverviewGrid<G, T> {
+    //categorize has to land every cell in one of the colors
+    pub fn new<F>(grid: G, colors: Vec<Color>, categorize: F) -> Self
+    where
+        F: Fn(T) -> usize + Send + Sync + 'static,
+    {
+        let Rect { width, height, .. } = grid.bounds();
+        let pyramid = Pyramid::from_fn(width, height, colors.len(), |p| categorize(grid.get(p)));
+        OverviewGrid {
+            grid,
+            pyramid,
+            colors,
+            categorize: Box::new(categorize),
+        }
+    }
+    pub fn total(&self, category: usize) -> u32 {
+        self.pyramid.total(category)
+    }
+    pub fn count(&self, level: usize, block: Point, category: usize) -> u32 {
+        self.pyramid.count(level, block, category)
+    }
+    //The most common category in each block of a level. Nothing is locked, so a block being written to while it's
+    //read can come out either way.
+    pub fn overview(&self, level: usize) -> WrappedGrid<usize> {
+        let (across, down) = self.pyramid.blocks(level);
+        let mut overview = WrappedGrid::new(across, down, 0);
+        for block in overview.bounds().points() {
+            overview.set(block, self.pyramid.majority(level, block));
+        }
+        overview
+    }
+    pub fn inner(&self) -> &G {
+        &self.grid
+    }
+}
@@ -0,0 +1,203 @@
+use super::*;
+use clap::Args;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+pub mod types {
+    pub use super::{Partition, PartitionArgs};
+}
+
+//These are consts because they're really for perf tuning
+const DEFAULT_REGIONS_PER_DIMENSION: usize = 8;
+//Enough regions that threads rarely land on the same one, not so many that every neighborhood straddles a few
+const REGIONS_PER_THREAD: usize = 4;
+
+//How many region locks a grid is split into along each axis. Sizes don't need to divide evenly -
+//the last column and row of regions just come out smaller.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Partition {
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl Partition {
+    pub fn new(columns: usize, rows: usize) -> Self {
+        Partition { columns, rows }
+    }
+    //About REGIONS_PER_THREAD regions per thread, shaped to keep each region close to square
+    pub fn auto(width: usize, height: usize, num_threads: usize) -> Self {
+        let target = (num_threads * REGIONS_PER_THREAD).max(1) as f64;
+        let columns = (target * width as f64 / height.max(1) as f64)
+            .sqrt()
+            .round() as usize;
+        let columns = columns.clamp(1, width.max(1));
+        let rows = (target / columns as f64).ceil() as usize;
+        Partition::new(columns, rows.clamp(1, height.max(1)))
+    }
+    pub fn count(&self) -> usize {
+        self.columns * self.rows
+    }
+}
+
+impl Default for Partition {
+    fn default() -> Self {
+        Partition::new(DEFAULT_REGIONS_PER_DIMENSION, DEFAULT_REGIONS_PER_DIMENSION)
+    }
+}
+
+impl Display for Partition {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}x{}", self.columns, self.rows)
+    }
+}
+
+//Written as COLUMNSxROWS, e.g. 8x4
+impl FromStr for Partition {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| match n.trim().parse::<usize>() {
+            Ok(0) | Err(_) => Err(format!("Expected COLUMNSxROWS, got {}", s)),
+            Ok(n) => Ok(n),
+        };
+        match s.split_once('x') {
+            Some((columns, rows)) => Ok(Partition::new(parse(columns)?, parse(rows)?)),
+            None => Err(format!("Expected COLUMNSxROWS, got {}", s)),
+        }
+    }
+}
+
+#[derive(Args, Debug, Copy, Clone)]
+pub struct PartitionArgs {
+    #[arg(long, default_value_t = Partition::default())]
+    pub regions: Partition,
+    #[arg(long, default_value_t = false, conflicts_with = "regions")]
+    pub auto_regions: bool,
+}
+
+impl PartitionArgs {
+    pub fn partition(&self, width: usize, height: usize, num_threads: usize) -> Partition {
+        if self.auto_regions {
+            Partition::auto(width, height, num_threads)
+        } else {
+            self.regions
+        }
+    }
+}
+
+//A partition applied to a particular grid. Regions are numbered row-major, and the squares in each are laid out
+//by layout.
+#[derive(Copy, Clone, Debug)]
+pub(super) struct RegionMap {
+    layout: Layout,
+    width: usize,
+    height: usize,
+    columns: usize,
+    rows: usize,
+    region_width: usize,
+    region_height: usize,
+}
+
+impl RegionMap {
+    pub(super) fn new(width: usize, height: usize, partition: Partition, layout: Layout) -> Self {
+        let region_width = width
+            .div_ceil(partition.columns.clamp(1, width.max(1)))
+            .max(1);
+        let region_height = height
+            .div_ceil(partition.rows.clamp(1, height.max(1)))
+            .max(1);
+        RegionMap {
+            layout,
+            width,
+            height,
+            //Rounding the region size up can leave fewer regions than asked for
+            columns: width.div_ceil(region_width),
+            rows: height.div_ceil(region_height),
+            region_width,
+            region_height,
+        }
+    }
+    pub(super) fn partition(&self) -> Partition {
+        Partition::new(self.columns, self.rows)
+    }
+    pub(super) fn count(&self) -> usize {
+        self.columns * self.rows
+    }
+    pub(super) fn rect(&self, region_index: usize) -> Rect {
+        let (x, y) = (
+            (region_index % self.columns) * self.region_width,
+            (region_index / self.columns) * self.region_height,
+        );
+        Rect::new(
+            Point(x, y),
+            usize::min(self.region_width, self.width - x),
+            usize::min(self.region_height, self.height - y),
+        )
+    }
+    //Storage needed for one region, padding included
+    pub(super) fn region_len(&self, region_index: usize) -> usize {
+        let rect = self.rect(region_index);
+        self.layout.len(rect.width, rect.height)
+    }
+    //p has to be in bounds already
+    pub(super) fn map_coordinates(&self, p: Point) -> (usize, usize) {
+        let Point(x, y) = p;
+        let region_index = (y / self.region_height) * self.columns + x / self.region_width;
+        let rect = self.rect(region_index);
+        let Point(x_in_region, y_in_region) = rect.local(p);
+        (
+            region_index,
+            self.layout
+                .index(rect.width, rect.height, x_in_region, y_in_region),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn auto_keeps_regions_square() {
+        assert_eq!(Partition::auto(100, 100, 4), Partition::new(4, 4));
+        assert_eq!(Partition::auto(400, 100, 4), Partition::new(8, 2));
+        //Never more regions than squares along either side, and always at least one
+        assert_eq!(Partition::auto(3, 2, 8), Partition::new(3, 2));
+        assert_eq!(Partition::auto(0, 0, 0), Partition::new(1, 1));
+        //Squareness wins over the thread count, so a strip gets cut into short lengths
+        assert_eq!(Partition::auto(1000, 1, 2), Partition::new(89, 1));
+    }
+
+    #[test]
+    fn ragged_edges_come_out_smaller() {
+        let map = RegionMap::new(10, 7, Partition::new(3, 3), Layout::RowMajor);
+        assert_eq!(map.partition(), Partition::new(3, 3));
+        assert_eq!(map.rect(0), Rect::new(Point(0, 0), 4, 3));
+        assert_eq!(map.rect(2), Rect::new(Point(8, 0), 2, 3));
+        assert_eq!(map.rect(8), Rect::new(Point(8, 6), 2, 1));
+        //Rounding 9 / 4 up to 3 wide only takes three columns
+        let map = RegionMap::new(9, 5, Partition::new(4, 1), Layout::RowMajor);
+        assert_eq!(map.partition(), Partition::new(3, 1));
+        //And a grid narrower than the partition gets one region per square
+        let map = RegionMap::new(5, 2, Partition::new(8, 8), Layout::RowMajor);
+        assert_eq!(map.partition(), Partition::new(5, 2));
+    }
+
+    #[test]
+    fn every_square_gets_its_own_slot() {
+        for layout in [Layout::RowMajor, Layout::Tiled, Layout::ZOrder] {
+            for (width, height) in [(10, 7), (130, 67), (1, 1)] {
+                let map = RegionMap::new(width, height, Partition::new(3, 3), layout);
+                let mut seen = HashSet::new();
+                for p in Rect::new(Point(0, 0), width, height).points() {
+                    let (region, index) = map.map_coordinates(p);
+                    assert!(map.rect(region).contains(p));
+                    assert!(index < map.region_len(region), "{layout:?} {p}");
+                    assert!(seen.insert((region, index)), "{layout:?} {p}");
+                }
+                let area: usize = (0..map.count()).map(|i| map.rect(i).area()).sum();
+                assert_eq!(area, width * height);
+            }
+        }
+    }
+}
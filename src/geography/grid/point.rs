@@ -1,11 +1,14 @@
+use super::*;
+use crate::geography::Direction;
+use std::ops::Add;
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Point(pub usize, pub usize);
 
 impl Point {
     #[inline(always)]
     pub fn distance(p1: Self, p2: Self) -> f64 {
-        let (x1, y1, x2, y2) = (p1.0 as i32, p1.1 as i32, p2.0 as i32, p2.1 as i32);
-        (((x1 - x2) * (x1 - x2) + (y1 - y2) * (y1 - y2)) as f64).sqrt()
+        Metric::Euclidean.measure((p1.0.abs_diff(p2.0), p1.1.abs_diff(p2.1)))
     }
     #[inline(always)]
     pub fn x(&self) -> usize {
@@ -15,11 +18,69 @@ impl Point {
     pub fn y(&self) -> usize {
         self.1
     }
+    //Back onto a width x height torus. Coordinates that wrapped below zero (see Add<Offset>) count as negative.
+    #[inline(always)]
+    pub fn wrap(self, width: usize, height: usize) -> Self {
+        Self(
+            (self.0 as isize).rem_euclid(width as isize) as usize,
+            (self.1 as isize).rem_euclid(height as isize) as usize,
+        )
+    }
+    #[inline(always)]
+    pub fn wrapping_offset(self, o: Offset, width: usize, height: usize) -> Self {
+        (self + o).wrap(width, height)
+    }
+    //The shortest step from here to q on a width x height torus, going round the back if that's quicker
+    #[inline(always)]
+    pub fn offset_to(self, q: Point, width: usize, height: usize) -> Offset {
+        Offset(
+            metric::wrapped_offset(self.0, q.0, width),
+            metric::wrapped_offset(self.1, q.1, height),
+        )
+    }
 }
 
-impl std::ops::Add for Point {
+impl Add for Point {
     type Output = Self;
     fn add(self, other: Self) -> Self {
         Self(self.0 + other.0, self.1 + other.1)
     }
 }
+
+impl Add<Offset> for Point {
+    type Output = Self;
+    fn add(self, o: Offset) -> Self {
+        Self(
+            self.0.wrapping_add_signed(o.0),
+            self.1.wrapping_add_signed(o.1),
+        )
+    }
+}
+
+impl<D: Direction> Add<D> for Point {
+    type Output = Self;
+    fn add(self, dir: D) -> Self {
+        self + dir.offset()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offset_to_takes_the_short_way_round() {
+        assert_eq!(
+            Point(0, 0).offset_to(Point(1023, 0), 1024, 16),
+            Offset(-1, 0)
+        );
+        assert_eq!(
+            Point(1023, 15).offset_to(Point(0, 0), 1024, 16),
+            Offset(1, 1)
+        );
+        assert_eq!(Point(2, 3).offset_to(Point(5, 1), 1024, 16), Offset(3, -2));
+        let p = Point(1020, 14);
+        let o = p.offset_to(Point(3, 2), 1024, 16);
+        assert_eq!(p.wrapping_offset(o, 1024, 16), Point(3, 2));
+    }
+}
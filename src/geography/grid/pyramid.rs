@@ -0,0 +1,173 @@
+use super::*;
+use std::sync::atomic::{AtomicU16, AtomicU32, Ordering};
+
+pub mod types {
+    pub use super::Pyramid;
+}
+
+//Levels finer than this are counted from the cells when asked, rather than stored. Keeping 2x2 blocks would cost
+//more memory than the cells themselves.
+const FINEST_STORED: usize = 2;
+
+//Per-block counts of each category of cell in a width x height area, at every power of two from single cells up to one
+//block covering everything. Level n has blocks 2^n cells on a side, the last ones clipped by the edge. Setting a cell
+//is O(levels) and takes no lock, but two writes to the same cell have to be ordered by the caller, or a count can
+//briefly dip below zero. OverviewGrid keeps one in step with a grid's contents, writing it under the grid's own locks.
+#[derive(Debug)]
+pub struct Pyramid {
+    width: usize,
+    height: usize,
+    categories: usize,
+    cells: Vec<AtomicU16>,
+    //From FINEST_STORED up
+    stored: Vec<Level>,
+}
+
+#[derive(Debug)]
+struct Level {
+    width: usize,
+    counts: Vec<AtomicU32>,
+}
+
+impl Pyramid {
+    //Everything starts out in category 0
+    pub fn new(width: usize, height: usize, categories: usize) -> Self {
+        if categories > u16::MAX as usize {
+            panic!("A pyramid can't have {categories} categories");
+        }
+        let top = usize::max(width, height)
+            .next_power_of_two()
+            .trailing_zeros() as usize;
+        let stored = (FINEST_STORED..=top)
+            .map(|level| {
+                let (across, down) = blocks(width, height, level);
+                let counts: Vec<AtomicU32> = (0..across * down * categories)
+                    .map(|_| AtomicU32::new(0))
+                    .collect();
+                for block in Rect::new(Point(0, 0), across, down).points() {
+                    let area = block_rect(width, height, level, block).area();
+                    counts[(block.1 * across + block.0) * categories]
+                        .store(area as u32, Ordering::Relaxed);
+                }
+                Level {
+                    width: across,
+                    counts,
+                }
+            })
+            .collect();
+        Pyramid {
+            width,
+            height,
+            categories,
+            cells: (0..width * height).map(|_| AtomicU16::new(0)).collect(),
+            stored,
+        }
+    }
+    pub fn from_fn<F: Fn(Point) -> usize>(
+        width: usize,
+        height: usize,
+        categories: usize,
+        categorize: F,
+    ) -> Self {
+        let pyramid = Self::new(width, height, categories);
+        for p in Rect::new(Point(0, 0), width, height).points() {
+            pyramid.set(p, categorize(p));
+        }
+        pyramid
+    }
+    //Points outside the area wrap back onto it
+    pub fn set(&self, p: Point, category: usize) {
+        let Point(x, y) = p.wrap(self.width, self.height);
+        let old = self.cells[y * self.width + x].swap(category as u16, Ordering::Relaxed) as usize;
+        if old == category {
+            return;
+        }
+        for (level, Level { width, counts }) in (FINEST_STORED..).zip(self.stored.iter()) {
+            let block = ((y >> level) * width + (x >> level)) * self.categories;
+            counts[block + old].fetch_sub(1, Ordering::Relaxed);
+            counts[block + category].fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    pub fn category(&self, p: Point) -> usize {
+        let Point(x, y) = p.wrap(self.width, self.height);
+        self.cells[y * self.width + x].load(Ordering::Relaxed) as usize
+    }
+    pub fn categories(&self) -> usize {
+        self.categories
+    }
+    //Including level 0, the cells themselves
+    pub fn levels(&self) -> usize {
+        self.top() + 1
+    }
+    //How many blocks across and down a level is
+    pub fn blocks(&self, level: usize) -> (usize, usize) {
+        blocks(self.width, self.height, level)
+    }
+    //The cells a block covers
+    pub fn block_rect(&self, level: usize, block: Point) -> Rect {
+        block_rect(self.width, self.height, level, block)
+    }
+    pub fn count(&self, level: usize, block: Point, category: usize) -> u32 {
+        match level.checked_sub(FINEST_STORED) {
+            Some(stored) => {
+                let Level { width, counts } = &self.stored[stored];
+                counts[(block.1 * width + block.0) * self.categories + category]
+                    .load(Ordering::Relaxed)
+            }
+            None => self
+                .block_rect(level, block)
+                .points()
+                .filter(|p| self.category(*p) == category)
+                .count() as u32,
+        }
+    }
+    //The most common category in the block, ties going to the lowest
+    pub fn majority(&self, level: usize, block: Point) -> usize {
+        match level.checked_sub(FINEST_STORED) {
+            Some(stored) => {
+                let Level { width, counts } = &self.stored[stored];
+                let start = (block.1 * width + block.0) * self.categories;
+                let counts = &counts[start..start + self.categories];
+                (0..self.categories)
+                    .rev()
+                    .max_by_key(|c| counts[*c].load(Ordering::Relaxed))
+                    .unwrap()
+            }
+            None if level == 0 => self.category(block),
+            None => (0..self.categories)
+                .rev()
+                .max_by_key(|c| self.count(level, block, *c))
+                .unwrap(),
+        }
+    }
+    //How many cells are in the category, over the whole area
+    pub fn total(&self, category: usize) -> u32 {
+        self.count(self.top(), Point(0, 0), category)
+    }
+    //The finest level that's no more than `across` blocks wide and high
+    pub fn level_for(&self, across: usize) -> usize {
+        let size = usize::max(self.width, self.height);
+        (0..=self.top())
+            .find(|level| size.div_ceil(1 << level) <= across)
+            .unwrap_or(self.top())
+    }
+    fn top(&self) -> usize {
+        usize::max(self.width, self.height)
+            .next_power_of_two()
+            .trailing_zeros() as usize
+    }
+}
+
+fn blocks(width: usize, height: usize, level: usize) -> (usize, usize) {
+    (width.div_ceil(1 << level), height.div_ceil(1 << level))
+}
+
+fn block_rect(width: usize, height: usize, level: usize, block: Point) -> Rect {
+    let (x, y) = (block.0 << level, block.1 << level);
+    let side = 1 << level;
+    Rect::new(
+        Point(x, y),
+        usize::min(side, width - x),
+        usize::min(side, height - y),
+    )
+}
@@ -0,0 +1,72 @@
+use super::*;
+
+//Corners use wrapping arithmetic, so a rect can straddle the origin of a ChunkedGrid
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub origin: Point,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(origin: Point, width: usize, height: usize) -> Self {
+        Rect {
+            origin,
+            width,
+            height,
+        }
+    }
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+    pub fn contains(&self, p: Point) -> bool {
+        p.0.wrapping_sub(self.origin.0) < self.width
+            && p.1.wrapping_sub(self.origin.1) < self.height
+    }
+    //Position of p relative to the origin
+    pub fn local(&self, p: Point) -> Point {
+        Point(
+            p.0.wrapping_sub(self.origin.0),
+            p.1.wrapping_sub(self.origin.1),
+        )
+    }
+    //The inverse of local
+    pub fn global(&self, p: Point) -> Point {
+        Point(
+            self.origin.0.wrapping_add(p.0),
+            self.origin.1.wrapping_add(p.1),
+        )
+    }
+    //Row-major, starting from the origin
+    pub fn points(&self) -> RectPoints {
+        RectPoints {
+            rect: *self,
+            index: 0,
+        }
+    }
+}
+
+pub struct RectPoints {
+    rect: Rect,
+    index: usize,
+}
+
+impl Iterator for RectPoints {
+    type Item = Point;
+    fn next(&mut self) -> Option<Point> {
+        if self.index >= self.rect.area() {
+            return None;
+        }
+        let Rect { origin, width, .. } = self.rect;
+        let p = Point(
+            origin.0.wrapping_add(self.index % width),
+            origin.1.wrapping_add(self.index / width),
+        );
+        self.index += 1;
+        Some(p)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.rect.area() - self.index;
+        (remaining, Some(remaining))
+    }
+}
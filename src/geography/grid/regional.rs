@@ -1,3 +1,2 @@
-mod graphics;
 mod regional_grid;
 pub use self::regional_grid::RegionalGrid;
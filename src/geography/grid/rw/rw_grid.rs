@@ -1,123 +1,115 @@
+use super::super::{contention::RegionLock, partition::RegionMap};
 #[allow(unused_imports)]
 use super::{
     super::{super::*, *},
     *,
 };
-use std::sync::RwLock;
+use crate::sync::{AtomicBool, Ordering};
 
-//These are consts because they're really for perf tuning, and they're here because their efficacy is impl-specific.
-pub(super) const REGIONS_PER_DIMENSION: usize = 8;
-pub(super) const TOTAL_REGIONS: usize = REGIONS_PER_DIMENSION * REGIONS_PER_DIMENSION;
 pub struct RwGrid<T> {
     pub(super) width: usize,
     pub(super) height: usize,
-    pub(super) region_size: usize,
-    pub(super) regions: [RwLock<Vec<T>>; TOTAL_REGIONS],
+    pub(super) region_map: RegionMap,
+    pub(super) regions: Vec<RegionLock<Vec<T>>>,
+    pub(super) instrumented: AtomicBool,
 }
 
 impl<T: Copy> Grid<T> for RwGrid<T> {
-    fn step<D: Direction>(&self, pt: Point, dir: D) -> Point {
-        let (x, y) = (pt.0 as i32, pt.1 as i32);
-        let (xs, ys) = dir.step();
-        let result = Point(modulo(x + xs, self.width), modulo(y + ys, self.height));
-        result
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        p.wrapping_offset(o, self.width, self.height)
     }
 
     fn get(&self, p: Point) -> T {
         let (region_index, index_in_region) = self.map_coordinates(p);
-        let region = self.regions[region_index].read().unwrap();
+        let region = self.regions[region_index].read(&self.instrumented);
         region[index_in_region]
     }
     fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
-        let p = self.fix(p);
-        Neighborhood {
-            c: self.get(p),
-            n: self.get(self.step(p, Compass::N)),
-            ne: self.get(self.step(p, Compass::NE)),
-            e: self.get(self.step(p, Compass::E)),
-            se: self.get(self.step(p, Compass::SE)),
-            s: self.get(self.step(p, Compass::S)),
-            sw: self.get(self.step(p, Compass::SW)),
-            w: self.get(self.step(p, Compass::W)),
-            nw: self.get(self.step(p, Compass::NW)),
-        }
-    }
-    fn set(&mut self, p: Point, value: T) {
-        let (region_index, index_in_region) = self.map_coordinates(p);
-        let mut region = self.regions[region_index].write().unwrap();
-        region[index_in_region] = value;
+        super::super::grid::read_regions(
+            Neighborhood::around(p).map(|q| self.map_coordinates(q)),
+            |region_index| self.regions[region_index].read(&self.instrumented),
+        )
     }
     fn rand(&self) -> Point {
         Point(roll::usize(self.width), roll::usize(self.height))
     }
-    fn distance(&self, pt1: Point, pt2: Point) -> f64 {
-        let x_dist = (pt1.0 as i32 - pt2.0 as i32) % (self.width / 2) as i32;
-        let y_dist = (pt1.1 as i32 - pt2.1 as i32) % (self.height / 2) as i32;
-        ((x_dist * x_dist + y_dist * y_dist) as f64).sqrt()
+    fn bounds(&self) -> Rect {
+        Rect::new(Point(0, 0), self.width, self.height)
     }
-
-    fn update<F>(&mut self, p: Point, update: F)
-    where
-        F: Fn(T) -> T,
-    {
-        let (region_index, index_in_region) = self.map_coordinates(p);
-        let region = self.regions[region_index].get_mut().unwrap();
-        let updated = update(region[index_in_region]);
-        region[index_in_region] = updated;
+    fn snapshot(&self) -> WrappedGrid<T> {
+        //Take every lock before copying anything, so the copy is of one moment in time
+        let regions: Vec<_> = self
+            .regions
+            .iter()
+            .map(|r| r.read(&self.instrumented))
+            .collect();
+        //A grid with no width or height has no regions, and no square to take a default from
+        let Some(first) = regions.first() else {
+            return WrappedGrid::empty(self.width, self.height);
+        };
+        let mut snapshot = WrappedGrid::new(self.width, self.height, first[0]);
+        for (i, region) in regions.iter().enumerate() {
+            for p in self.region_map.rect(i).points() {
+                snapshot.set(p, region[self.map_coordinates(p).1]);
+            }
+        }
+        snapshot
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        p1.offset_to(p2, self.width, self.height)
     }
 }
 
 impl<T: Copy> ThreadedGrid<T> for RwGrid<T> {
-    fn set_if<F>(&self, p: Point, f: F, value: T) -> bool
+    fn regions(&self) -> Vec<Rect> {
+        (0..self.region_map.count())
+            .map(|i| self.region_map.rect(i))
+            .collect()
+    }
+    fn path<F>(&self, p: Point, step: &mut F) -> PathResult
     where
-        F: Fn(T) -> bool,
+        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+    {
+        super::super::grid::locked_path(self, p, step)
+    }
+    fn set_instrumented(&self, on: bool) {
+        if on {
+            self.regions.iter().for_each(|region| region.reset());
+        }
+        self.instrumented.store(on, Ordering::Relaxed);
+    }
+    fn instrumented(&self) -> bool {
+        self.instrumented.load(Ordering::Relaxed)
+    }
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        self.regions
+            .iter()
+            .enumerate()
+            .map(|(i, region)| region.stats(self.region_map.rect(i)))
+            .collect()
+    }
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
     {
         let (region_index, index_in_region) = self.map_coordinates(p);
-        let mut region = self.regions[region_index].write().unwrap();
+        let mut region = self.regions[region_index].write(&self.instrumented);
         let pre_existing = region[index_in_region];
-        if f(pre_existing) {
+        if let Some(value) = f(pre_existing) {
             region[index_in_region] = value;
-            true
-        } else {
-            false
         }
+        pre_existing
     }
-
-    fn path<F>(&self, _p: Point, _step: &mut F) -> PathResult
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
     where
-        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+        F: FnOnce(&mut [T]) -> bool,
     {
-        todo!()
-        // use self::Compass::*;
-        // let mut p = p;
-        // let mut dir: Option<Compass> = None;
-        // loop {
-        //     if let Some(dir) = dir {
-        //         p = self.step(p, dir)
-        //     }
-        //     let mut locked = self.lock_hood(p);
-        //     let neighborhood = Neighborhood {
-        //         c: *locked.get(None),
-        //         n: *locked.get(Some(N)),
-        //         ne: *locked.get(Some(NE)),
-        //         e: *locked.get(Some(E)),
-        //         se: *locked.get(Some(SE)),
-        //         s: *locked.get(Some(S)),
-        //         sw: *locked.get(Some(SW)),
-        //         w: *locked.get(Some(W)),
-        //         nw: *locked.get(Some(NW)),
-        //     };
-        //     match step(neighborhood) {
-        //         StepResult::Step(d) => dir = d,
-        //         StepResult::Stick(t) => {
-        //             println!("Sticking!");
-        //             *locked.get(None) = t;
-        //             return PathResult::Stuck(p);
-        //         }
-        //         StepResult::Die => return PathResult::Died(p),
-        //         StepResult::Change(n) => todo!(),
-        //     }
-        // }
+        let cells: Vec<_> = points.iter().map(|p| self.map_coordinates(*p)).collect();
+        super::super::grid::transact_regions(
+            &cells,
+            |i| self.regions[i].write(&self.instrumented),
+            f,
+        )
     }
 }
 impl<T: Copy> RwGrid<T> {
@@ -126,42 +118,39 @@ impl<T: Copy> RwGrid<T> {
     // }
 
     pub fn new(width: usize, height: usize, default: T) -> Self {
-        if width % REGIONS_PER_DIMENSION != 0 {
-            panic!(
-                "Width ({}) must be a multiple of {}",
-                width, REGIONS_PER_DIMENSION
-            )
-        };
-        if height % REGIONS_PER_DIMENSION != 0 {
-            panic!(
-                "Height ({}) must be a multiple of {}",
-                height, REGIONS_PER_DIMENSION
-            )
-        };
-        let region_size = height * width / TOTAL_REGIONS;
-        let regions = [0; TOTAL_REGIONS].map(|_| RwLock::new(vec![default; region_size]));
+        Self::with_partition(width, height, default, Partition::default())
+    }
+    pub fn with_partition(width: usize, height: usize, default: T, partition: Partition) -> Self {
+        Self::with_layout(width, height, default, partition, Layout::default())
+    }
+    pub fn with_layout(
+        width: usize,
+        height: usize,
+        default: T,
+        partition: Partition,
+        layout: Layout,
+    ) -> Self {
+        let region_map = RegionMap::new(width, height, partition, layout);
+        let regions = (0..region_map.count())
+            .map(|i| RegionLock::new(vec![default; region_map.region_len(i)]))
+            .collect();
         RwGrid {
             width,
             height,
-            region_size,
+            region_map,
             regions,
+            instrumented: AtomicBool::new(false),
         }
     }
+    //The partition actually in use, which can have fewer regions than asked for on small grids
+    pub fn partition(&self) -> Partition {
+        self.region_map.partition()
+    }
     pub(super) fn map_coordinates(&self, p: Point) -> (usize, usize) {
-        let Point(x, y) = self.fix(p);
-        let (region_width, region_height) = (
-            self.width / REGIONS_PER_DIMENSION,
-            self.height / REGIONS_PER_DIMENSION,
-        );
-        let (target_square_x, target_square_y) = (x / region_width, y / region_height);
-        let target_square_i = target_square_y * REGIONS_PER_DIMENSION + target_square_x;
-        let (x_in_square, y_in_square) = (x % region_width, y % region_width);
-        let index_in_square = y_in_square * region_width + x_in_square;
-        (target_square_i, index_in_square)
+        self.region_map.map_coordinates(self.fix(p))
     }
     pub(super) fn fix(&self, p: Point) -> Point {
-        let Point(x, y) = p;
-        Point(modulo(x as i32, self.width), modulo(y as i32, self.height))
+        p.wrap(self.width, self.height)
     }
     //fn lock_hood(&self, p: Point) -> LockedHood<T> {
     //     use self::Compass::*;
@@ -204,3 +193,34 @@ impl<T: Copy> RwGrid<T> {
     //     }
     // }
 }
+
+impl<T: Copy> BuildGrid<T> for RwGrid<T> {
+    fn build(width: usize, height: usize, default: T) -> Self {
+        Self::new(width, height, default)
+    }
+    fn build_with(
+        width: usize,
+        height: usize,
+        default: T,
+        partition: Partition,
+        layout: Layout,
+    ) -> Self {
+        Self::with_layout(width, height, default, partition, layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geography::*;
+
+    #[test]
+    fn snapshots_of_nothing() {
+        for (width, height) in [(0, 5), (5, 0), (0, 0)] {
+            let snapshot = RwGrid::new(width, height, 1u8).snapshot();
+            assert_eq!(snapshot.bounds(), Rect::new(Point(0, 0), width, height));
+        }
+        let snapshot = RwGrid::with_partition(5, 3, 2u8, Partition::new(2, 2)).snapshot();
+        assert_eq!(snapshot.bounds().area(), 15);
+        assert!(snapshot.bounds().points().all(|p| snapshot.get(p) == 2));
+    }
+}
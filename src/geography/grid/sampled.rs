@@ -0,0 +1,3 @@
+mod graphics;
+mod sampled_grid;
+pub use self::sampled_grid::SampledGrid;
@@ -0,0 +1,9 @@
+use super::sampled_grid::*;
+use crate::utils::draw_utils::Draw;
+use cairo::Context;
+
+impl<G: Draw, T> Draw for SampledGrid<G, T> {
+    fn draw(&self, context: &Context) {
+        self.grid.draw(context);
+    }
+}
@@ -0,0 +1,133 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::sync::Mutex;
+
+//A threaded grid that also keeps a Sampler of its cells, weighted by their contents, so "somewhere empty" doesn't
+//mean rolling rand() until it comes up. Only writes made through the wrapper are seen.
+//Every write that changes a weight takes the one sampler lock, so those writers queue up behind each other.
+pub struct SampledGrid<G, T> {
+    pub(super) grid: G,
+    sampler: Mutex<Sampler>,
+    weigh: Box<dyn Fn(T) -> u32 + Send + Sync>,
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> Grid<T> for SampledGrid<G, T> {
+    fn get(&self, p: Point) -> T {
+        self.grid.get(p)
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        self.grid.get_neighborhood(p)
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        self.grid.offset(p, o)
+    }
+    fn rand(&self) -> Point {
+        self.grid.rand()
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        self.grid.between(p1, p2)
+    }
+    fn bounds(&self) -> Rect {
+        self.grid.bounds()
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.grid.snapshot()
+    }
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> ThreadedGrid<T> for SampledGrid<G, T> {
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let pre_existing = self.grid.fetch_update(p, &f);
+        //f has no side effects, so asking it again is the cheapest way to find out what we wrote
+        if let Some(value) = f(pre_existing) {
+            if (self.weigh)(value) != (self.weigh)(pre_existing) {
+                self.refresh(&[p]);
+            }
+        }
+        pre_existing
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let commit = self.grid.transact(points, f);
+        if commit {
+            self.refresh(points);
+        }
+        commit
+    }
+    //Steps have to go through our transact to be indexed
+    fn path<F>(&self, p: Point, step: &mut F) -> PathResult
+    where
+        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+    {
+        super::super::grid::locked_path(self, p, step)
+    }
+    fn regions(&self) -> Vec<Rect> {
+        self.grid.regions()
+    }
+    fn set_instrumented(&self, on: bool) {
+        self.grid.set_instrumented(on);
+    }
+    fn instrumented(&self) -> bool {
+        self.grid.instrumented()
+    }
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        self.grid.lock_stats()
+    }
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> SampledGrid<G, T> {
+    //Index the cells matching f, all equally likely
+    pub fn new<F>(grid: G, f: F) -> Self
+    where
+        F: Fn(T) -> bool + Send + Sync + 'static,
+    {
+        Self::weighted(grid, move |t| f(t) as u32)
+    }
+    //Index the cells with non-zero weight
+    pub fn weighted<F>(grid: G, weigh: F) -> Self
+    where
+        F: Fn(T) -> u32 + Send + Sync + 'static,
+    {
+        let bounds = grid.bounds();
+        let sampler = Sampler::from_fn(bounds.width, bounds.height, |p| weigh(grid.get(p)));
+        SampledGrid {
+            grid,
+            sampler: Mutex::new(sampler),
+            weigh: Box::new(weigh),
+        }
+    }
+    //A random matching cell, or None if nothing matches. Someone may have changed it by the time you look, so
+    //claim it with set_if.
+    pub fn sample(&self) -> Option<Point> {
+        self.sampler.lock().unwrap().sample()
+    }
+    pub fn sample_weighted(&self) -> Option<Point> {
+        self.sampler.lock().unwrap().sample_weighted()
+    }
+    pub fn sample_in(&self, rect: Rect) -> Option<Point> {
+        self.sampler.lock().unwrap().sample_in(rect)
+    }
+    pub fn matching(&self) -> usize {
+        self.sampler.lock().unwrap().len()
+    }
+    pub fn inner(&self) -> &G {
+        &self.grid
+    }
+    //Re-read rather than trusting what the writer thinks it wrote, so whichever of two racing writers gets here
+    //last leaves the index matching the grid
+    fn refresh(&self, points: &[Point]) {
+        let mut sampler = self.sampler.lock().unwrap();
+        for p in points {
+            let p = self.grid.offset(*p, Offset::ZERO);
+            sampler.set_weight(p, (self.weigh)(self.grid.get(p)));
+        }
+    }
+}
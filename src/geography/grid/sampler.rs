@@ -0,0 +1,158 @@
+use super::super::*;
+use super::*;
+
+pub mod types {
+    pub use super::Sampler;
+}
+
+//How many random members sample_in tries before giving up and scanning the rect
+const REJECTION_TRIES: usize = 32;
+
+//An index of the cells in a width x height area with non-zero weight. Uniform sampling is O(1) and weighted
+//sampling is O(log n), however full the area gets. SampledGrid keeps one in step with a grid's contents.
+#[derive(Clone, Debug)]
+pub struct Sampler {
+    width: usize,
+    height: usize,
+    //Everything with a weight, in no particular order
+    members: Vec<Point>,
+    //Where each cell is in members, if it is
+    slots: Vec<Option<usize>>,
+    weights: Vec<u32>,
+    //Fenwick tree over weights, one-based
+    sums: Vec<u64>,
+}
+
+impl Sampler {
+    pub fn new(width: usize, height: usize) -> Self {
+        Sampler {
+            width,
+            height,
+            members: Vec::new(),
+            slots: vec![None; width * height],
+            weights: vec![0; width * height],
+            sums: vec![0; width * height + 1],
+        }
+    }
+    pub fn from_fn<F: Fn(Point) -> u32>(width: usize, height: usize, weigh: F) -> Self {
+        let mut sampler = Self::new(width, height);
+        for p in Rect::new(Point(0, 0), width, height).points() {
+            sampler.set_weight(p, weigh(p));
+        }
+        sampler
+    }
+    //Points outside the area wrap back onto it
+    pub fn set_weight(&mut self, p: Point, weight: u32) {
+        let p = p.wrap(self.width, self.height);
+        let i = p.1 * self.width + p.0;
+        let old = self.weights[i];
+        if old == weight {
+            return;
+        }
+        self.weights[i] = weight;
+        self.add(i, weight as i64 - old as i64);
+        match (self.slots[i], weight) {
+            (None, w) if w > 0 => {
+                self.slots[i] = Some(self.members.len());
+                self.members.push(p);
+            }
+            (Some(slot), 0) => {
+                self.slots[i] = None;
+                self.members.swap_remove(slot);
+                if let Some(&moved) = self.members.get(slot) {
+                    self.slots[moved.1 * self.width + moved.0] = Some(slot);
+                }
+            }
+            _ => (),
+        }
+    }
+    pub fn insert(&mut self, p: Point) {
+        self.set_weight(p, 1);
+    }
+    pub fn remove(&mut self, p: Point) {
+        self.set_weight(p, 0);
+    }
+    pub fn weight(&self, p: Point) -> u32 {
+        let p = p.wrap(self.width, self.height);
+        self.weights[p.1 * self.width + p.0]
+    }
+    pub fn contains(&self, p: Point) -> bool {
+        self.weight(p) > 0
+    }
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+    pub fn total_weight(&self) -> u64 {
+        self.prefix(self.weights.len())
+    }
+    //Every member equally likely, whatever its weight
+    pub fn sample(&self) -> Option<Point> {
+        match self.members.len() {
+            0 => None,
+            n => Some(self.members[roll::usize(n)]),
+        }
+    }
+    //Members in proportion to their weight
+    pub fn sample_weighted(&self) -> Option<Point> {
+        let total = self.total_weight();
+        if total == 0 {
+            return None;
+        }
+        //Walk down the tree to the first cell whose running total passes the target
+        let mut target = rand::random::<u64>() % total;
+        let (mut i, mut step) = (0, self.sums.len().next_power_of_two() / 2);
+        while step > 0 {
+            if i + step < self.sums.len() && self.sums[i + step] <= target {
+                i += step;
+                target -= self.sums[i];
+            }
+            step /= 2;
+        }
+        Some(Point(i % self.width, i / self.width))
+    }
+    //A uniformly chosen member inside the rect. Guesses from all the members first, which is quick when the rect holds
+    //a fair share of them, then falls back to scanning the rect.
+    pub fn sample_in(&self, rect: Rect) -> Option<Point> {
+        //Rects can hang off the edge and wrap round
+        let inside = |p: Point| {
+            let Point(x, y) = rect.local(p).wrap(self.width, self.height);
+            x < rect.width && y < rect.height
+        };
+        if !self.members.is_empty() {
+            for _ in 0..REJECTION_TRIES {
+                let p = self.members[roll::usize(self.members.len())];
+                if inside(p) {
+                    return Some(p);
+                }
+            }
+        }
+        let found: Vec<Point> = rect
+            .points()
+            .map(|p| p.wrap(self.width, self.height))
+            .filter(|p| self.contains(*p))
+            .collect();
+        match found.len() {
+            0 => None,
+            n => Some(found[roll::usize(n)]),
+        }
+    }
+    fn add(&mut self, i: usize, delta: i64) {
+        let mut j = i + 1;
+        while j < self.sums.len() {
+            self.sums[j] = (self.sums[j] as i64 + delta) as u64;
+            j += j & j.wrapping_neg();
+        }
+    }
+    //Total weight of the first n cells
+    fn prefix(&self, n: usize) -> u64 {
+        let (mut j, mut total) = (n, 0);
+        while j > 0 {
+            total += self.sums[j];
+            j -= j & j.wrapping_neg();
+        }
+        total
+    }
+}
@@ -0,0 +1,3 @@
+mod graphics;
+mod view_grid;
+pub use self::view_grid::View;
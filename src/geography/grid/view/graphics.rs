@@ -0,0 +1,11 @@
+use super::view_grid::*;
+use crate::geography::Grid;
+use crate::utils::{color::types::*, draw_utils, draw_utils::Draw};
+use cairo::Context;
+
+//Fills the window, so draw it into a translated and scaled context to get an inset
+impl<'a, T: Colored + Copy, G: Grid<T> + ?Sized> Draw for View<'a, T, G> {
+    fn draw(&self, context: &Context) {
+        draw_utils::grid_helper(context, self);
+    }
+}
@@ -0,0 +1,75 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::marker::PhantomData;
+
+//A borrowed rectangle of another grid, addressed from its own top left corner. Points past the edge wrap around
+//the view rather than reaching into the rest of the grid, so algorithms run on a view stay inside it.
+pub struct View<'a, T, G: ?Sized> {
+    pub(super) grid: &'a G,
+    rect: Rect,
+    phantom: PhantomData<T>,
+}
+
+impl<'a, T: Copy, G: Grid<T> + ?Sized> Grid<T> for View<'a, T, G> {
+    fn get(&self, p: Point) -> T {
+        self.grid.get(self.parent(p))
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        Neighborhood::around(p).map(|q| self.get(q))
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        p.wrapping_offset(o, self.rect.width, self.rect.height)
+    }
+    fn rand(&self) -> Point {
+        Point(roll::usize(self.rect.width), roll::usize(self.rect.height))
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        p1.offset_to(p2, self.rect.width, self.rect.height)
+    }
+    fn bounds(&self) -> Rect {
+        Rect::new(Point(0, 0), self.rect.width, self.rect.height)
+    }
+}
+
+//Writes land in the underlying grid
+impl<'a, T: Copy, G: ThreadedGrid<T> + ?Sized> ThreadedGrid<T> for View<'a, T, G> {
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        self.grid.fetch_update(self.parent(p), f)
+    }
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let points: Vec<Point> = points.iter().map(|p| self.parent(*p)).collect();
+        self.grid.transact(&points, f)
+    }
+    fn regions(&self) -> Vec<Rect> {
+        vec![self.bounds()]
+    }
+}
+
+impl<'a, T: Copy, G: Grid<T> + ?Sized> View<'a, T, G> {
+    pub fn new(grid: &'a G, rect: Rect) -> Self {
+        View {
+            grid,
+            rect,
+            phantom: PhantomData,
+        }
+    }
+    //Where the view's rect sits in the underlying grid
+    pub fn rect(&self) -> Rect {
+        self.rect
+    }
+    //The underlying grid's point for a local one
+    pub fn parent(&self, p: Point) -> Point {
+        let Point(x, y) = p.wrap(self.rect.width, self.rect.height);
+        self.grid
+            .offset(self.rect.origin, Offset(x as isize, y as isize))
+    }
+}
@@ -0,0 +1,3 @@
+mod graphics;
+mod watched_grid;
+pub use self::watched_grid::WatchedGrid;
@@ -0,0 +1,159 @@
+#[allow(unused_imports)]
+use super::{
+    super::{super::*, *},
+    *,
+};
+use std::sync::mpsc::Receiver;
+
+//A threaded grid that publishes every write made through it to a Feed, including ones that leave the cell as it was.
+//Changes are published while the write still holds its locks, so each point's changes arrive in the order they happened.
+pub struct WatchedGrid<G, T> {
+    pub(super) grid: G,
+    feed: Feed<T>,
+}
+
+impl<T: Copy, G: ThreadedGrid<T>> Grid<T> for WatchedGrid<G, T> {
+    fn get(&self, p: Point) -> T {
+        self.grid.get(p)
+    }
+    fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
+        self.grid.get_neighborhood(p)
+    }
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        self.grid.offset(p, o)
+    }
+    fn rand(&self) -> Point {
+        self.grid.rand()
+    }
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        self.grid.between(p1, p2)
+    }
+    fn bounds(&self) -> Rect {
+        self.grid.bounds()
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.grid.snapshot()
+    }
+}
+
+impl<T: Copy + Send, G: ThreadedGrid<T>> ThreadedGrid<T> for WatchedGrid<G, T> {
+    //f may run more than once in the inner grid's fetch_update, so go through transact, where it runs once under the
+    //lock and publishing can happen alongside it
+    fn fetch_update<F>(&self, p: Point, f: F) -> T
+    where
+        F: Fn(T) -> Option<T>,
+    {
+        let mut pre_existing = None;
+        self.transact(&[p], |cells| {
+            pre_existing = Some(cells[0]);
+            match f(cells[0]) {
+                Some(value) => {
+                    cells[0] = value;
+                    true
+                }
+                None => false,
+            }
+        });
+        pre_existing.unwrap()
+    }
+    //Points are wrapped beforehand, as the grid can't be called back into while it holds them
+    fn transact<F>(&self, points: &[Point], f: F) -> bool
+    where
+        F: FnOnce(&mut [T]) -> bool,
+    {
+        let points: Vec<Point> = points
+            .iter()
+            .map(|p| self.grid.offset(*p, Offset::ZERO))
+            .collect();
+        self.grid.transact(&points, |cells| {
+            let before = cells.to_vec();
+            let commit = f(cells);
+            if commit {
+                for ((&p, old), &new) in points.iter().zip(before).zip(cells.iter()) {
+                    self.feed.publish(Change { p, old, new });
+                }
+            }
+            commit
+        })
+    }
+    fn path<F>(&self, p: Point, step: &mut F) -> PathResult
+    where
+        F: FnMut(Neighborhood<T>) -> StepResult<T>,
+    {
+        super::super::grid::locked_path(self, p, step)
+    }
+    fn regions(&self) -> Vec<Rect> {
+        self.grid.regions()
+    }
+    fn set_instrumented(&self, on: bool) {
+        self.grid.set_instrumented(on);
+    }
+    fn instrumented(&self) -> bool {
+        self.grid.instrumented()
+    }
+    fn lock_stats(&self) -> Vec<RegionStats> {
+        self.grid.lock_stats()
+    }
+}
+
+impl<T: Copy + Send, G: ThreadedGrid<T>> WatchedGrid<G, T> {
+    pub fn new(grid: G) -> Self {
+        WatchedGrid {
+            grid,
+            feed: Feed::new(),
+        }
+    }
+    pub fn subscribe(&self) -> Receiver<Change<T>> {
+        self.feed.subscribe()
+    }
+    pub fn inner(&self) -> &G {
+        &self.grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geography::*;
+    use crate::utils::color;
+
+    //LockedGrid's one lock isn't reentrant, so anything the wrappers ask of it mid-transaction would hang these
+    #[test]
+    fn wraps_a_locked_grid() {
+        let grid = OverviewGrid::new(
+            WatchedGrid::new(LockedGrid::new(WrappedGrid::new(4, 4, 0u32))),
+            vec![color::BLACK; 2],
+            |v| (v > 0) as usize,
+        );
+        let changes = grid.inner().subscribe();
+        grid.set(Point(5, 1), 3);
+        assert!(grid.set_if(Point(2, 2), |v| v == 0, 1));
+        grid.update(Point(1, 1), |v| v + 1);
+        assert!(grid.transact(&[Point(0, 3), Point(3, 3)], |cells| {
+            cells[1] = 7;
+            true
+        }));
+        assert!(matches!(
+            grid.path(Point(0, 0), &mut |_| StepResult::Stick(9)),
+            PathResult::Stuck(Point(0, 0))
+        ));
+        assert_eq!(grid.total(1), 4);
+        //Sticking commits the whole neighborhood the path locked, so it's seen as nine changes
+        let seen: Vec<Change<u32>> = changes.try_iter().collect();
+        let change = |p, old, new| Change { p, old, new };
+        assert_eq!(seen.len(), 14);
+        assert_eq!(seen[0], change(Point(1, 1), 0, 3));
+        assert_eq!(seen[2], change(Point(1, 1), 3, 4));
+        assert_eq!(seen[5], change(Point(0, 0), 0, 9));
+
+        let watched = WatchedGrid::new(OverviewGrid::new(
+            LockedGrid::new(WrappedGrid::new(4, 4, 0u32)),
+            vec![color::BLACK; 2],
+            |v| (v > 0) as usize,
+        ));
+        let changes = watched.subscribe();
+        watched.set(Point(0, 4), 2);
+        assert!(!watched.set_if(Point(0, 0), |v| v == 0, 5));
+        assert_eq!(watched.inner().total(1), 1);
+        assert_eq!(changes.try_iter().count(), 1);
+    }
+}
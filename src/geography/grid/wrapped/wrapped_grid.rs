@@ -4,69 +4,96 @@ use super::{
     *,
 };
 
+#[derive(Clone)]
 pub struct WrappedGrid<T: Copy> {
     pub(super) height: usize,
     pub(super) width: usize,
-    pub(super) grid: Vec<Vec<T>>,
+    pub(super) layout: Layout,
+    pub(super) grid: Vec<T>,
 }
 
 impl<T: Copy> Grid<T> for WrappedGrid<T> {
     #[inline(always)]
     fn get(&self, p: Point) -> T {
-        let Point(x, y) = self.fix(p);
-        self.grid[x][y]
+        self.grid[self.index(p)]
     }
     fn get_neighborhood(&self, p: Point) -> Neighborhood<T> {
-        Neighborhood::local().map(|a| {
-            self.get(Point(
-                modulo(p.0 as i32 + a.0, self.width),
-                modulo(p.1 as i32 + a.1, self.height),
-            ))
-        })
+        Neighborhood::local().map(|a| self.get(self.offset(p, a.into())))
     }
     #[inline(always)]
-    fn set(&mut self, p: Point, value: T) {
-        let Point(x, y) = self.fix(p);
-        self.grid[x][y] = value;
+    fn offset(&self, p: Point, o: Offset) -> Point {
+        p.wrapping_offset(o, self.width, self.height)
     }
     #[inline(always)]
-    fn step<D: Direction>(&self, pt: Point, dir: D) -> Point {
-        let (x, y) = (pt.0 as i32, pt.1 as i32);
-        let (xs, ys) = dir.step();
-        let result = Point(modulo(x + xs, self.width), modulo(y + ys, self.height));
-        result
-    }
-    #[inline(always)]
-    fn distance(&self, pt1: Point, pt2: Point) -> f64 {
-        let x_dist = (pt1.0 as i32 - pt2.0 as i32) % (self.width / 2) as i32;
-        let y_dist = (pt1.1 as i32 - pt2.1 as i32) % (self.height / 2) as i32;
-        ((x_dist * x_dist + y_dist * y_dist) as f64).sqrt()
+    fn between(&self, p1: Point, p2: Point) -> Offset {
+        p1.offset_to(p2, self.width, self.height)
     }
     fn rand(&self) -> Point {
         Point(roll::usize(self.width), roll::usize(self.height))
     }
+    fn bounds(&self) -> Rect {
+        Rect::new(Point(0, 0), self.width, self.height)
+    }
+    fn snapshot(&self) -> WrappedGrid<T> {
+        self.clone()
+    }
+}
 
+impl<T: Copy> GridMut<T> for WrappedGrid<T> {
+    #[inline(always)]
+    fn set(&mut self, p: Point, value: T) {
+        let i = self.index(p);
+        self.grid[i] = value;
+    }
     fn update<F>(&mut self, p: Point, update: F)
     where
         F: Fn(T) -> T,
     {
-        let Point(x, y) = self.fix(p);
-        let updated = update(self.grid[x][y]);
-        self.grid[x][y] = updated;
+        let i = self.index(p);
+        self.grid[i] = update(self.grid[i]);
     }
 }
 
 impl<T: Copy> WrappedGrid<T> {
-    pub fn new(h: usize, w: usize, default: T) -> Self {
+    pub fn new(w: usize, h: usize, default: T) -> Self {
+        Self::with_layout(w, h, default, Layout::default())
+    }
+    pub fn with_layout(w: usize, h: usize, default: T, layout: Layout) -> Self {
         WrappedGrid::<T> {
             width: w,
             height: h,
-            grid: vec![vec!(default; h); w],
+            layout,
+            grid: vec![default; layout.len(w, h)],
+        }
+    }
+    //For when h or w is 0, so there's no square to take a default from
+    pub fn empty(w: usize, h: usize) -> Self {
+        if h != 0 && w != 0 {
+            panic!("A {w}x{h} grid isn't empty");
+        }
+        WrappedGrid::<T> {
+            width: w,
+            height: h,
+            layout: Layout::default(),
+            grid: Vec::new(),
         }
     }
     #[inline(always)]
+    fn index(&self, p: Point) -> usize {
+        let Point(x, y) = self.fix(p);
+        self.layout.index(self.width, self.height, x, y)
+    }
+    #[inline(always)]
     fn fix(&self, p: Point) -> Point {
-        let Point(x, y) = p;
-        Point(modulo(x as i32, self.width), modulo(y as i32, self.width))
+        p.wrap(self.width, self.height)
+    }
+}
+
+impl<T: Copy> BuildGrid<T> for WrappedGrid<T> {
+    fn build(width: usize, height: usize, default: T) -> Self {
+        Self::new(width, height, default)
+    }
+    fn build_with(width: usize, height: usize, default: T, _: Partition, layout: Layout) -> Self {
+        Self::with_layout(width, height, default, layout)
     }
 }
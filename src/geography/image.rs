@@ -0,0 +1,69 @@
+//PNGs at one pixel per square, for saving worlds and hand-painting new ones
+use super::*;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+//Colors only compare equal to 8 bits a channel, so that's what gets written and matched
+fn rgb8(c: Color) -> [u8; 3] {
+    [c.r, c.g, c.b].map(|channel| (channel * 255.0).round() as u8)
+}
+
+//Everything inside bounds(), so unbounded grids come out as just the part that's been written
+pub fn save<T, G, P>(grid: &G, path: P) -> Result<(), String>
+where
+    T: Colored + Copy,
+    G: Grid<T> + ?Sized,
+    P: AsRef<Path>,
+{
+    let snapshot = grid.snapshot();
+    let Rect { width, height, .. } = snapshot.bounds();
+    let mut data = Vec::with_capacity(width * height * 3);
+    for (_, t) in snapshot.iter() {
+        data.extend(rgb8(t.color()));
+    }
+    let file = File::create(path.as_ref()).map_err(|e| e.to_string())?;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+    writer.write_image_data(&data).map_err(|e| e.to_string())
+}
+
+//Each pixel becomes whatever the palette pairs its color with. Any color not in the palette is an error, since it's
+//usually a stray brush stroke.
+pub fn load<T, P>(path: P, palette: &[(Color, T)]) -> Result<WrappedGrid<T>, String>
+where
+    T: Copy,
+    P: AsRef<Path>,
+{
+    let file = File::open(path.as_ref()).map_err(|e| e.to_string())?;
+    let mut decoder = png::Decoder::new(file);
+    //Down to 8 bits a channel, with palettes expanded to plain RGB
+    decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+    let mut reader = decoder.read_info().map_err(|e| e.to_string())?;
+    let mut data = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut data).map_err(|e| e.to_string())?;
+    let (width, height) = (info.width as usize, info.height as usize);
+    let channels = info.color_type.samples();
+    let lookup: Vec<([u8; 3], T)> = palette.iter().map(|(c, t)| (rgb8(*c), *t)).collect();
+    let pixel = |p: Point| {
+        let i = (p.1 * info.line_size) + p.0 * channels;
+        match info.color_type {
+            png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha => [data[i]; 3],
+            _ => [data[i], data[i + 1], data[i + 2]],
+        }
+    };
+    let find = |p: Point| {
+        let rgb = pixel(p);
+        match lookup.iter().find(|(c, _)| *c == rgb) {
+            Some((_, t)) => Ok(*t),
+            None => Err(format!("{:?} at {} isn't in the palette", rgb, p)),
+        }
+    };
+    let mut grid = WrappedGrid::new(width, height, find(Point(0, 0))?);
+    for p in grid.bounds().points() {
+        grid.set(p, find(p)?);
+    }
+    Ok(grid)
+}
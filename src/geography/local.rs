@@ -1,5 +1,9 @@
 pub mod direction;
 pub use self::direction::types::*;
 
+pub mod mask;
+pub use self::mask::{Mask, MaskTable};
 pub mod neighborhood;
 pub use self::neighborhood::Neighborhood;
+pub mod stencil;
+pub use self::stencil::Stencil;
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 use super::*;
+use crate::geography::Offset;
 use std::fmt::Display;
 
 pub mod types {
@@ -8,6 +9,9 @@ pub mod types {
 
 pub trait Direction: Clone + Copy + Display + From<usize> + PartialEq + Eq {
     fn step(&self) -> (i32, i32);
+    fn offset(&self) -> Offset {
+        self.step().into()
+    }
     fn left(&self) -> Self;
     fn right(&self) -> Self;
     fn reverse(&self) -> Self;
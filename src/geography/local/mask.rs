@@ -0,0 +1,79 @@
+use super::*;
+use std::ops::{BitAnd, BitOr, Index, Not};
+
+//Which of the eight squares around a point matched something, one bit per Compass direction (N is bit 0, going
+//clockwise). The center isn't included.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Mask(pub u8);
+
+impl Mask {
+    pub const EMPTY: Mask = Mask(0);
+    pub const FULL: Mask = Mask(u8::MAX);
+    pub fn of<T: Copy, F: Fn(T) -> bool>(n: &Neighborhood<T>, f: F) -> Self {
+        Self::from_fn(|dir| f(n.from_dir(dir)))
+    }
+    pub fn from_fn<F: Fn(Compass) -> bool>(f: F) -> Self {
+        (0..8)
+            .filter(|&i| f(Compass::from(i)))
+            .fold(Self::EMPTY, |mask, i| Mask(mask.0 | 1 << i))
+    }
+    pub fn contains(&self, dir: Compass) -> bool {
+        self.0 & 1 << dir as u8 != 0
+    }
+    pub fn count(&self) -> u32 {
+        self.0.count_ones()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+    //The directions set, clockwise from N
+    pub fn dirs(&self) -> impl Iterator<Item = Compass> + '_ {
+        (0..8).map(Compass::from).filter(|dir| self.contains(*dir))
+    }
+    //The same mask as seen by something facing dir, so that dir becomes N. Lets one table serve every heading.
+    pub fn facing(&self, dir: Compass) -> Self {
+        Mask(self.0.rotate_right(dir as u32))
+    }
+}
+
+impl BitAnd for Mask {
+    type Output = Self;
+    fn bitand(self, other: Self) -> Self {
+        Mask(self.0 & other.0)
+    }
+}
+
+impl BitOr for Mask {
+    type Output = Self;
+    fn bitor(self, other: Self) -> Self {
+        Mask(self.0 | other.0)
+    }
+}
+
+impl Not for Mask {
+    type Output = Self;
+    fn not(self) -> Self {
+        Mask(!self.0)
+    }
+}
+
+//A precomputed answer for every possible mask, so per-step decisions are a single lookup
+#[derive(Clone)]
+pub struct MaskTable<A> {
+    entries: [A; 256],
+}
+
+impl<A> MaskTable<A> {
+    pub fn new<F: Fn(Mask) -> A>(f: F) -> Self {
+        Self {
+            entries: std::array::from_fn(|i| f(Mask(i as u8))),
+        }
+    }
+}
+
+impl<A> Index<Mask> for MaskTable<A> {
+    type Output = A;
+    fn index(&self, mask: Mask) -> &A {
+        &self.entries[mask.0 as usize]
+    }
+}
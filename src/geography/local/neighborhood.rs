@@ -1,4 +1,5 @@
 use super::*;
+use crate::geography::{Offset, Point};
 use std::iter::FromIterator;
 pub struct Neighborhood<T> {
     pub c: T,
@@ -28,6 +29,22 @@ impl Neighborhood<(i32, i32)> {
     }
 }
 
+impl Neighborhood<Point> {
+    //The points themselves, wrapping like Point does. It's up to the grid to make sense of them.
+    pub fn around(p: Point) -> Neighborhood<Point> {
+        Neighborhood::local().map(|a| p + Offset::from(a))
+    }
+}
+
+impl<T> Neighborhood<T> {
+    //Centre first, then clockwise from north, the same order as iterating
+    pub fn to_array(self) -> [T; 9] {
+        [
+            self.c, self.n, self.ne, self.e, self.se, self.s, self.sw, self.w, self.nw,
+        ]
+    }
+}
+
 impl<T: Copy> Neighborhood<T> {
     pub fn from_dir(&self, dir: Compass) -> T {
         use super::Compass::*;
@@ -60,16 +77,67 @@ impl<T: Copy> Neighborhood<T> {
     }
 }
 
+//Turning and mirroring, for rules that shouldn't care which way up they're applied
+impl<T: Copy> Neighborhood<T> {
+    //A quarter turn clockwise: what was to the north ends up to the east
+    pub fn rotate_right(&self) -> Neighborhood<T> {
+        Neighborhood {
+            c: self.c,
+            n: self.w,
+            ne: self.nw,
+            e: self.n,
+            se: self.ne,
+            s: self.e,
+            sw: self.se,
+            w: self.s,
+            nw: self.sw,
+        }
+    }
+    pub fn rotate_left(&self) -> Neighborhood<T> {
+        self.rotate_right().rotate_right().rotate_right()
+    }
+    //Quarter turns to the right. Negative turns go left.
+    pub fn rotate(&self, quarter_turns: isize) -> Neighborhood<T> {
+        (0..quarter_turns.rem_euclid(4)).fold(self.map(|t| t), |n, _| n.rotate_right())
+    }
+    //Mirrored left to right
+    pub fn reflect(&self) -> Neighborhood<T> {
+        Neighborhood {
+            c: self.c,
+            n: self.n,
+            ne: self.nw,
+            e: self.w,
+            se: self.sw,
+            s: self.s,
+            sw: self.se,
+            w: self.e,
+            nw: self.ne,
+        }
+    }
+    //The four rotations, then the four rotations of the reflection
+    pub fn symmetries(&self) -> Vec<Neighborhood<T>> {
+        let reflected = self.reflect();
+        (0..4)
+            .map(|turns| self.rotate(turns))
+            .chain((0..4).map(|turns| reflected.rotate(turns)))
+            .collect()
+    }
+    //Whether the rule matches any way round
+    pub fn matches_any<F>(&self, rule: F) -> bool
+    where
+        F: Fn(&Neighborhood<T>) -> bool,
+    {
+        self.symmetries().iter().any(rule)
+    }
+}
+
 impl<T> IntoIterator for Neighborhood<T> {
     type Item = T;
 
-    type IntoIter = std::vec::IntoIter<Self::Item>;
+    type IntoIter = std::array::IntoIter<Self::Item, 9>;
 
     fn into_iter(self) -> Self::IntoIter {
-        vec![
-            self.c, self.n, self.ne, self.e, self.se, self.s, self.sw, self.w, self.nw,
-        ]
-        .into_iter()
+        self.to_array().into_iter()
     }
 }
 impl<T> FromIterator<T> for Neighborhood<T> {
@@ -0,0 +1,138 @@
+use super::*;
+use crate::geography::Offset;
+
+//Which squares around a point matter, as offsets from it. Read them with Grid::neighbors.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Stencil {
+    offsets: Vec<Offset>,
+}
+
+impl Stencil {
+    pub fn new(offsets: Vec<Offset>) -> Self {
+        Self { offsets }
+    }
+    pub fn of<D: Direction>(directions: &[D]) -> Self {
+        Self::new(directions.iter().map(|d| d.offset()).collect())
+    }
+    //Cardinal gives von Neumann radius 1, Compass gives Moore radius 1, in the order D::all() lists them
+    pub fn all<D: Direction>() -> Self {
+        Self::of(&D::all())
+    }
+    //Everything within radius steps, diagonals not allowed
+    pub fn von_neumann(radius: usize) -> Self {
+        Self::square(radius, |o| {
+            o.0.unsigned_abs() + o.1.unsigned_abs() <= radius
+        })
+    }
+    //Everything within radius steps, diagonals allowed
+    pub fn moore(radius: usize) -> Self {
+        Self::square(radius, |_| true)
+    }
+    pub fn knight() -> Self {
+        Self::new(vec![Offset(1, -2)])
+            .symmetries()
+            .iter()
+            .map(|s| s.offsets[0])
+            .collect()
+    }
+    //The point itself, first
+    pub fn with_center(mut self) -> Self {
+        if !self.offsets.contains(&Offset::ZERO) {
+            self.offsets.insert(0, Offset::ZERO);
+        }
+        self
+    }
+    pub fn offsets(&self) -> &[Offset] {
+        &self.offsets
+    }
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.offsets.is_empty()
+    }
+    //Quarter turns to the right. Negative turns go left.
+    pub fn rotate(&self, quarter_turns: isize) -> Self {
+        self.offsets
+            .iter()
+            .map(|o| o.rotate(quarter_turns))
+            .collect()
+    }
+    //Mirrored left to right
+    pub fn reflect(&self) -> Self {
+        self.offsets.iter().map(|o| Offset(-o.0, o.1)).collect()
+    }
+    //The four rotations, then the four rotations of the reflection. Symmetric stencils will repeat themselves.
+    pub fn symmetries(&self) -> Vec<Self> {
+        let reflected = self.reflect();
+        (0..4)
+            .map(|turns| self.rotate(turns))
+            .chain((0..4).map(|turns| reflected.rotate(turns)))
+            .collect()
+    }
+    //Row by row, skipping the center
+    fn square<F: Fn(Offset) -> bool>(radius: usize, keep: F) -> Self {
+        let r = radius as isize;
+        (-r..=r)
+            .flat_map(|y| (-r..=r).map(move |x| Offset(x, y)))
+            .filter(|o| *o != Offset::ZERO && keep(*o))
+            .collect()
+    }
+}
+
+impl FromIterator<Offset> for Stencil {
+    fn from_iter<I: IntoIterator<Item = Offset>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    fn cells(stencil: &Stencil) -> HashSet<Offset> {
+        stencil.offsets().iter().copied().collect()
+    }
+
+    #[test]
+    fn rotations_turn_right() {
+        let hook = Stencil::new(vec![Offset(0, -1), Offset(1, -2)]);
+        assert_eq!(hook.rotate(1).offsets(), [Offset(1, 0), Offset(2, 1)]);
+        assert_eq!(hook.rotate(2).offsets(), [Offset(0, 1), Offset(-1, 2)]);
+        assert_eq!(hook.rotate(-1), hook.rotate(3));
+        assert_eq!(hook.rotate(4), hook);
+        assert_eq!(hook.rotate(1).rotate(-1), hook);
+    }
+
+    #[test]
+    fn reflections_mirror_left_to_right() {
+        let hook = Stencil::new(vec![Offset(0, -1), Offset(1, -2)]);
+        assert_eq!(hook.reflect().offsets(), [Offset(0, -1), Offset(-1, -2)]);
+        assert_eq!(hook.reflect().reflect(), hook);
+        //Turning the mirror image right is turning the original left
+        assert_eq!(hook.rotate(1).reflect(), hook.reflect().rotate(-1));
+    }
+
+    #[test]
+    fn symmetries_cover_every_orientation() {
+        let hook = Stencil::new(vec![Offset(0, -1), Offset(1, -2)]);
+        let symmetries = hook.symmetries();
+        assert_eq!(symmetries.len(), 8);
+        assert_eq!(symmetries[0], hook);
+        assert_eq!(symmetries[4], hook.reflect());
+        let distinct: HashSet<_> = symmetries.iter().map(|s| s.offsets().to_vec()).collect();
+        assert_eq!(distinct.len(), 8);
+        //Only the order changes for stencils that are symmetric already
+        for stencil in [
+            Stencil::moore(2),
+            Stencil::von_neumann(2),
+            Stencil::knight(),
+        ] {
+            for symmetry in stencil.symmetries() {
+                assert_eq!(cells(&symmetry), cells(&stencil));
+            }
+        }
+        assert_eq!(cells(&Stencil::knight()).len(), 8);
+    }
+}
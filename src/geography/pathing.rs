@@ -0,0 +1,245 @@
+use super::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+pub mod types {
+    pub use super::{DistanceField, Path};
+}
+
+#[derive(Clone, Debug)]
+pub struct Path {
+    pub points: Vec<Point>,
+    pub cost: f64,
+}
+
+impl Path {
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+}
+
+//Cost to reach every point found from the nearest source. Also works as a flow field - walk downhill to get home.
+pub struct DistanceField {
+    distances: HashMap<Point, f64>,
+}
+
+impl DistanceField {
+    pub fn get(&self, p: Point) -> Option<f64> {
+        self.distances.get(&p).copied()
+    }
+    pub fn len(&self) -> usize {
+        self.distances.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.distances.is_empty()
+    }
+    //The move that gets closest to a source, or None if p is unreachable or already a local minimum
+    pub fn downhill<T, G, D>(&self, grid: &G, p: Point, moves: &[D]) -> Option<D>
+    where
+        T: Copy,
+        G: Grid<T>,
+        D: Direction,
+    {
+        let here = self.get(p)?;
+        moves
+            .iter()
+            .filter_map(|dir| self.get(grid.step(p, *dir)).map(|d| (*dir, d)))
+            .filter(|(_, d)| *d < here)
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(dir, _)| dir)
+    }
+}
+
+//Fewest moves from `from` to `to`, ignoring cost entirely
+pub fn bfs<T, G, D, F>(grid: &G, from: Point, to: Point, moves: &[D], passable: F) -> Option<Path>
+where
+    T: Copy,
+    G: Grid<T>,
+    D: Direction,
+    F: Fn(Point, T) -> bool,
+{
+    let mut parents: HashMap<Point, Option<Point>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    parents.insert(from, None);
+    queue.push_back(from);
+    while let Some(p) = queue.pop_front() {
+        if p == to {
+            let points = unwind(to, |p| parents[&p]);
+            let cost = (points.len() - 1) as f64;
+            return Some(Path { points, cost });
+        }
+        for dir in moves {
+            let next = grid.step(p, *dir);
+            if !parents.contains_key(&next) && passable(next, grid.get(next)) {
+                parents.insert(next, Some(p));
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+//`cost` is the price of entering a square (None for impassable), scaled by the length of the move
+pub fn dijkstra<T, G, D, F>(grid: &G, from: Point, to: Point, moves: &[D], cost: F) -> Option<Path>
+where
+    T: Copy,
+    G: Grid<T>,
+    D: Direction,
+    F: Fn(Point, T) -> Option<f64>,
+{
+    let found = search(grid, &[from], Some(to), moves, &cost, |_| 0.0, None);
+    path_to(&found, to)
+}
+
+//As dijkstra, guided by the heuristic metric. Only optimal if the metric never overestimates the real cost.
+pub fn astar<T, G, D, F>(
+    grid: &G,
+    from: Point,
+    to: Point,
+    moves: &[D],
+    cost: F,
+    heuristic: Metric,
+) -> Option<Path>
+where
+    T: Copy,
+    G: Grid<T>,
+    D: Direction,
+    F: Fn(Point, T) -> Option<f64>,
+{
+    let estimate = |p| grid.metric_distance(p, to, heuristic);
+    let found = search(grid, &[from], Some(to), moves, &cost, estimate, None);
+    path_to(&found, to)
+}
+
+//Searches outward from every source at once. Stops at max_cost if given, which unbounded grids will need.
+pub fn distance_field<T, G, D, F>(
+    grid: &G,
+    sources: &[Point],
+    moves: &[D],
+    cost: F,
+    max_cost: Option<f64>,
+) -> DistanceField
+where
+    T: Copy,
+    G: Grid<T>,
+    D: Direction,
+    F: Fn(Point, T) -> Option<f64>,
+{
+    let found = search(grid, sources, None, moves, &cost, |_| 0.0, max_cost);
+    let distances = found.into_iter().map(|(p, (d, _))| (p, d)).collect();
+    DistanceField { distances }
+}
+
+struct Frontier {
+    priority: f64,
+    cost: f64,
+    p: Point,
+}
+impl PartialEq for Frontier {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+impl Eq for Frontier {}
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Frontier {
+    //Reversed, so the BinaryHeap pops the cheapest first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .priority
+            .partial_cmp(&self.priority)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+type Found = HashMap<Point, (f64, Option<Point>)>;
+
+fn search<T, G, D, F, H>(
+    grid: &G,
+    sources: &[Point],
+    target: Option<Point>,
+    moves: &[D],
+    cost: &F,
+    heuristic: H,
+    max_cost: Option<f64>,
+) -> Found
+where
+    T: Copy,
+    G: Grid<T>,
+    D: Direction,
+    F: Fn(Point, T) -> Option<f64>,
+    H: Fn(Point) -> f64,
+{
+    let mut found: Found = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for source in sources {
+        found.insert(*source, (0.0, None));
+        heap.push(Frontier {
+            priority: heuristic(*source),
+            cost: 0.0,
+            p: *source,
+        });
+    }
+    while let Some(Frontier {
+        cost: so_far, p, ..
+    }) = heap.pop()
+    {
+        if Some(p) == target {
+            break;
+        }
+        //Stale entry - we've already been here more cheaply
+        if so_far > found[&p].0 {
+            continue;
+        }
+        for dir in moves {
+            let next = grid.step(p, *dir);
+            let entry_cost = match cost(next, grid.get(next)) {
+                None => continue,
+                Some(c) => c,
+            };
+            let move_length = dir.offset().length();
+            let total = so_far + entry_cost * move_length;
+            if max_cost.is_some_and(|max| total > max) {
+                continue;
+            }
+            if found.get(&next).is_none_or(|(known, _)| total < *known) {
+                found.insert(next, (total, Some(p)));
+                heap.push(Frontier {
+                    priority: total + heuristic(next),
+                    cost: total,
+                    p: next,
+                });
+            }
+        }
+    }
+    found
+}
+
+fn path_to(found: &Found, to: Point) -> Option<Path> {
+    let cost = found.get(&to)?.0;
+    Some(Path {
+        points: unwind(to, |p| found[&p].1),
+        cost,
+    })
+}
+
+fn unwind<F>(to: Point, parent: F) -> Vec<Point>
+where
+    F: Fn(Point) -> Option<Point>,
+{
+    let mut points = vec![to];
+    let mut p = to;
+    while let Some(next) = parent(p) {
+        points.push(next);
+        p = next;
+    }
+    points.reverse();
+    points
+}
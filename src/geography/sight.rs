@@ -0,0 +1,372 @@
+//Seeing further than a Neighborhood: lines across the grid, rays cast until they hit something, and the nearest
+//matching cell in range. Lines are drawn in offsets from the viewer, so they follow the grid's topology over any edges.
+use super::*;
+use clap::ValueEnum;
+use std::cmp::Ordering;
+
+pub mod types {
+    pub use super::{Sighting, Traversal};
+}
+
+#[derive(ValueEnum, Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Traversal {
+    //One cell per step along the longer axis, so a line can slip diagonally between two cells touching at a corner
+    Bresenham,
+    //Every cell the line passes through, and both cells either side when it passes exactly through a corner
+    Supercover,
+}
+
+impl Traversal {
+    //From (0, 0) to o inclusive
+    pub fn offsets(&self, o: Offset) -> Vec<Offset> {
+        match self {
+            Traversal::Bresenham => bresenham(o),
+            Traversal::Supercover => supercover(o),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sighting<T> {
+    pub p: Point,
+    pub value: T,
+    //From the viewer, so its length is how far away it was seen
+    pub offset: Offset,
+}
+
+//The cells from `from` to `to`, both included, going the shortest way round the grid
+pub fn line<T, G>(grid: &G, from: Point, to: Point, traversal: Traversal) -> Vec<Point>
+where
+    T: Copy,
+    G: Grid<T> + ?Sized,
+{
+    traversal
+        .offsets(grid.between(from, to))
+        .into_iter()
+        .map(|o| grid.offset(from, o))
+        .collect()
+}
+
+//Whether nothing between the two points (but not the points themselves) is opaque
+pub fn line_of_sight<T, G, F>(
+    grid: &G,
+    from: Point,
+    to: Point,
+    traversal: Traversal,
+    opaque: F,
+) -> bool
+where
+    T: Copy,
+    G: Grid<T> + ?Sized,
+    F: Fn(Point, T) -> bool,
+{
+    let points = line(grid, from, to, traversal);
+    points
+        .iter()
+        .skip(1)
+        .take(points.len().saturating_sub(2))
+        .all(|p| !opaque(*p, grid.get(*p)))
+}
+
+//The first cell past `from` that f picks, heading towards `toward` for up to range cells along the longer axis
+pub fn cast<T, G, F>(
+    grid: &G,
+    from: Point,
+    toward: Offset,
+    range: usize,
+    traversal: Traversal,
+    f: F,
+) -> Option<Sighting<T>>
+where
+    T: Copy,
+    G: Grid<T> + ?Sized,
+    F: Fn(Point, T) -> bool,
+{
+    let longest = isize::max(toward.0.abs(), toward.1.abs());
+    if longest == 0 {
+        return None;
+    }
+    //Stretch or shrink the heading until it's range long
+    let reach = |d: isize| (d as f64 * range as f64 / longest as f64).round() as isize;
+    let target = Offset(reach(toward.0), reach(toward.1));
+    traversal
+        .offsets(target)
+        .into_iter()
+        .skip(1)
+        .map(|offset| {
+            let p = grid.offset(from, offset);
+            Sighting {
+                p,
+                value: grid.get(p),
+                offset,
+            }
+        })
+        .find(|sighting| f(sighting.p, sighting.value))
+}
+
+//The closest cell within radius that f picks, other than `from` itself. Ties go to whichever's found first.
+//Searches outwards in square rings, stopping once no ring further out could hold anything closer.
+pub fn nearest<T, G, F>(
+    grid: &G,
+    from: Point,
+    radius: f64,
+    metric: Metric,
+    f: F,
+) -> Option<Sighting<T>>
+where
+    T: Copy,
+    G: Grid<T> + ?Sized,
+    F: Fn(Point, T) -> bool,
+{
+    let mut best: Option<(f64, Sighting<T>)> = None;
+    //Every metric is at least the Chebyshev distance, which is the ring number
+    for ring in 1..=radius.floor() as isize {
+        if best.is_some_and(|(distance, _)| distance < ring as f64) {
+            break;
+        }
+        for offset in ring_offsets(ring) {
+            let distance = metric.measure(offset.delta());
+            if distance > radius || best.is_some_and(|(d, _)| d <= distance) {
+                continue;
+            }
+            let p = grid.offset(from, offset);
+            let value = grid.get(p);
+            if f(p, value) {
+                best = Some((distance, Sighting { p, value, offset }));
+            }
+        }
+    }
+    best.map(|(_, sighting)| sighting)
+}
+
+//Everything exactly ring cells away along the longer axis, clockwise from the top left
+fn ring_offsets(ring: isize) -> impl Iterator<Item = Offset> {
+    let top = (-ring..ring).map(move |x| Offset(x, -ring));
+    let right = (-ring..ring).map(move |y| Offset(ring, y));
+    let bottom = (-ring..ring).map(move |x| Offset(-x, ring));
+    let left = (-ring..ring).map(move |y| Offset(-ring, -y));
+    top.chain(right).chain(bottom).chain(left)
+}
+
+fn bresenham(o: Offset) -> Vec<Offset> {
+    let (dx, dy) = (o.0.abs(), -o.1.abs());
+    let (sx, sy) = (o.0.signum(), o.1.signum());
+    let (mut x, mut y, mut error) = (0, 0, dx + dy);
+    let mut offsets = vec![Offset::ZERO];
+    while (x, y) != (o.0, o.1) {
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+        offsets.push(Offset(x, y));
+    }
+    offsets
+}
+
+fn supercover(o: Offset) -> Vec<Offset> {
+    let (nx, ny) = (o.0.abs(), o.1.abs());
+    let (sx, sy) = (o.0.signum(), o.1.signum());
+    let (mut x, mut y, mut ix, mut iy) = (0, 0, 0, 0);
+    let mut offsets = vec![Offset::ZERO];
+    while ix < nx || iy < ny {
+        //Which side of the line the next corner is on, in units that keep everything integer
+        match ((1 + 2 * ix) * ny).cmp(&((1 + 2 * iy) * nx)) {
+            Ordering::Equal => {
+                offsets.push(Offset(x + sx, y));
+                offsets.push(Offset(x, y + sy));
+                x += sx;
+                y += sy;
+                ix += 1;
+                iy += 1;
+            }
+            Ordering::Less => {
+                x += sx;
+                ix += 1;
+            }
+            Ordering::Greater => {
+                y += sy;
+                iy += 1;
+            }
+        }
+        offsets.push(Offset(x, y));
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walled(walls: &[Point]) -> WrappedGrid<bool> {
+        let mut grid = WrappedGrid::new(8, 8, false);
+        for p in walls {
+            grid.set(*p, true);
+        }
+        grid
+    }
+
+    #[test]
+    fn lines_run_end_to_end() {
+        let flipped = |offsets: Vec<Offset>| -> Vec<Offset> {
+            offsets.into_iter().map(|q| Offset(-q.0, -q.1)).collect()
+        };
+        for dx in -5..=5 {
+            for dy in -5..=5 {
+                let o = Offset(dx, dy);
+                let line = bresenham(o);
+                assert_eq!((line[0], *line.last().unwrap()), (Offset::ZERO, o));
+                assert_eq!(line.len(), dx.unsigned_abs().max(dy.unsigned_abs()) + 1);
+                assert_eq!(bresenham(Offset(-dx, -dy)), flipped(line));
+                let cover = supercover(o);
+                assert_eq!((cover[0], *cover.last().unwrap()), (Offset::ZERO, o));
+                //One cell per unit moved along either axis, plus the extra side at each corner
+                assert!(cover.len() > dx.unsigned_abs() + dy.unsigned_abs());
+                assert_eq!(supercover(Offset(-dx, -dy)), flipped(cover.clone()));
+                //Walking it from the other end covers the same cells
+                let mut there: Vec<_> = cover.iter().map(|q| (o.0 - q.0, o.1 - q.1)).collect();
+                let mut here: Vec<_> = cover.iter().map(|q| (q.0, q.1)).collect();
+                there.sort();
+                here.sort();
+                assert_eq!(here, there);
+            }
+        }
+    }
+
+    #[test]
+    fn only_bresenham_cuts_corners() {
+        assert_eq!(
+            bresenham(Offset(2, 2)),
+            vec![Offset(0, 0), Offset(1, 1), Offset(2, 2)]
+        );
+        assert_eq!(
+            supercover(Offset(2, 2)),
+            vec![
+                Offset(0, 0),
+                Offset(1, 0),
+                Offset(0, 1),
+                Offset(1, 1),
+                Offset(2, 1),
+                Offset(1, 2),
+                Offset(2, 2)
+            ]
+        );
+        let grid = walled(&[Point(3, 2), Point(2, 3)]);
+        let (from, to) = (Point(2, 2), Point(3, 3));
+        let opaque = |_, wall| wall;
+        assert!(line_of_sight(&grid, from, to, Traversal::Bresenham, opaque));
+        assert!(!line_of_sight(
+            &grid,
+            from,
+            to,
+            Traversal::Supercover,
+            opaque
+        ));
+    }
+
+    #[test]
+    fn lines_wrap_across_the_edge() {
+        let grid = walled(&[]);
+        assert_eq!(
+            line(&grid, Point(7, 0), Point(1, 7), Traversal::Bresenham),
+            vec![Point(7, 0), Point(0, 7), Point(1, 7)]
+        );
+        let grid = walled(&[Point(1, 3)]);
+        let sighting = cast(
+            &grid,
+            Point(6, 3),
+            Offset(1, 0),
+            4,
+            Traversal::Bresenham,
+            |_, wall| wall,
+        );
+        assert_eq!(
+            sighting,
+            Some(Sighting {
+                p: Point(1, 3),
+                value: true,
+                offset: Offset(3, 0)
+            })
+        );
+        let sighting = nearest(&grid, Point(6, 4), 3.0, Metric::Chebyshev, |_, wall| wall);
+        assert_eq!(sighting.map(|s| s.offset), Some(Offset(3, -1)));
+    }
+
+    #[test]
+    fn walls_block_the_view() {
+        let grid = walled(&[Point(2, 0), Point(4, 0)]);
+        let opaque = |_, wall| wall;
+        //The ends of a line don't block it
+        assert!(line_of_sight(
+            &grid,
+            Point(0, 0),
+            Point(2, 0),
+            Traversal::Supercover,
+            opaque
+        ));
+        assert!(!line_of_sight(
+            &grid,
+            Point(0, 0),
+            Point(3, 0),
+            Traversal::Supercover,
+            opaque
+        ));
+        //Casting stops at the first wall, and sees nothing if it runs out of range first
+        let hit = cast(
+            &grid,
+            Point(0, 0),
+            Offset(5, 0),
+            6,
+            Traversal::Supercover,
+            opaque,
+        );
+        assert_eq!(hit.map(|s| s.p), Some(Point(2, 0)));
+        let miss = cast(
+            &grid,
+            Point(0, 0),
+            Offset(5, 0),
+            1,
+            Traversal::Supercover,
+            opaque,
+        );
+        assert_eq!(miss, None);
+        assert_eq!(
+            cast(
+                &grid,
+                Point(0, 0),
+                Offset::ZERO,
+                6,
+                Traversal::Supercover,
+                opaque
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn nearest_goes_by_metric() {
+        let grid = walled(&[Point(5, 5), Point(3, 0)]);
+        let wall = |_, wall| wall;
+        let found = |from, radius, metric| nearest(&grid, from, radius, metric, wall).map(|s| s.p);
+        //Two steps diagonally is nearer than three straight, except in Manhattan
+        assert_eq!(
+            found(Point(3, 3), 4.0, Metric::Chebyshev),
+            Some(Point(5, 5))
+        );
+        assert_eq!(
+            found(Point(3, 3), 4.0, Metric::Euclidean),
+            Some(Point(5, 5))
+        );
+        assert_eq!(
+            found(Point(3, 3), 4.0, Metric::Manhattan),
+            Some(Point(3, 0))
+        );
+        assert_eq!(found(Point(3, 3), 2.5, Metric::Manhattan), None);
+        //Where you're standing doesn't count
+        assert_eq!(found(Point(5, 5), 1.0, Metric::Chebyshev), None);
+    }
+}
@@ -0,0 +1,104 @@
+//Whole-grid operations that make new grids, for composing patterns and tiles into bigger worlds.
+//Everything works in local coordinates, with the origin at the corner of the source's bounds().
+use super::*;
+
+//Just the rect, copied out
+pub fn crop<T: Copy, G: Grid<T> + ?Sized>(grid: &G, rect: Rect) -> WrappedGrid<T> {
+    grid.view(rect).snapshot()
+}
+
+//The grid repeated columns times across and rows times down
+pub fn tile<T: Copy, G: Grid<T> + ?Sized>(grid: &G, columns: usize, rows: usize) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    //An empty grid tiled is still empty, whatever the counts
+    if bounds.area() == 0 {
+        return WrappedGrid::empty(0, 0);
+    }
+    build(
+        bounds.width * columns,
+        bounds.height * rows,
+        |Point(x, y)| grid.get(bounds.global(Point(x % bounds.width, y % bounds.height))),
+    )
+}
+
+//Stretched or shrunk to width x height, each square taking the value of the nearest source square
+pub fn resize<T: Copy, G: Grid<T> + ?Sized>(
+    grid: &G,
+    width: usize,
+    height: usize,
+) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    //Nothing to stretch
+    if bounds.area() == 0 {
+        return WrappedGrid::empty(0, 0);
+    }
+    build(width, height, |Point(x, y)| {
+        grid.get(bounds.global(Point(x * bounds.width / width, y * bounds.height / height)))
+    })
+}
+
+//Each square blown up into a factor x factor block
+pub fn upscale<T: Copy, G: Grid<T> + ?Sized>(grid: &G, factor: usize) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    resize(grid, bounds.width * factor, bounds.height * factor)
+}
+
+//Quarter turns clockwise. Negative turns go anticlockwise.
+pub fn rotate<T: Copy, G: Grid<T> + ?Sized>(grid: &G, quarter_turns: isize) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    let Rect { width, height, .. } = bounds;
+    let at = |x, y| grid.get(bounds.global(Point(x, y)));
+    match quarter_turns.rem_euclid(4) {
+        0 => build(width, height, |Point(x, y)| at(x, y)),
+        1 => build(height, width, |Point(x, y)| at(y, height - 1 - x)),
+        2 => build(width, height, |Point(x, y)| {
+            at(width - 1 - x, height - 1 - y)
+        }),
+        _ => build(height, width, |Point(x, y)| at(width - 1 - y, x)),
+    }
+}
+
+//Mirrored left to right
+pub fn flip_horizontal<T: Copy, G: Grid<T> + ?Sized>(grid: &G) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    let Rect { width, height, .. } = bounds;
+    build(width, height, |Point(x, y)| {
+        grid.get(bounds.global(Point(width - 1 - x, y)))
+    })
+}
+
+//Mirrored top to bottom
+pub fn flip_vertical<T: Copy, G: Grid<T> + ?Sized>(grid: &G) -> WrappedGrid<T> {
+    let bounds = grid.bounds();
+    let Rect { width, height, .. } = bounds;
+    build(width, height, |Point(x, y)| {
+        grid.get(bounds.global(Point(x, height - 1 - y)))
+    })
+}
+
+//Copies all of source into target with its corner at `at`, going through target's topology for anything that
+//hangs off the edge
+pub fn paste<T, S, G>(target: &mut G, source: &S, at: Point)
+where
+    T: Copy,
+    S: Grid<T> + ?Sized,
+    G: GridMut<T> + ?Sized,
+{
+    let bounds = source.bounds();
+    for (p, t) in source.iter() {
+        let Point(x, y) = bounds.local(p);
+        let q = target.offset(at, Offset(x as isize, y as isize));
+        target.set(q, t);
+    }
+}
+
+fn build<T: Copy, F: Fn(Point) -> T>(width: usize, height: usize, f: F) -> WrappedGrid<T> {
+    if width == 0 || height == 0 {
+        return WrappedGrid::empty(width, height);
+    }
+    let mut built = WrappedGrid::new(width, height, f(Point(0, 0)));
+    for p in Rect::new(Point(0, 0), width, height).points() {
+        built.set(p, f(p));
+    }
+    built
+}
@@ -0,0 +1,196 @@
+//Named areas of a world, for burgs to spawn in, aim for or give their own rules
+use super::*;
+use std::ops::Index;
+use std::path::Path;
+
+mod graphics;
+
+pub mod types {
+    pub use super::{Zone, Zones};
+}
+
+//How many random members sample_where tries before giving up and scanning them all
+const REJECTION_TRIES: usize = 32;
+
+//Any set of cells in a world. However it was shaped, it's kept as a mask plus a list of its members, so contains is one
+//lookup and sample is one roll.
+#[derive(Clone)]
+pub struct Zone {
+    cells: WrappedGrid<bool>,
+    members: Vec<Point>,
+    pub color: Color,
+}
+
+impl Zone {
+    //The cells of the grid's bounds that f picks
+    pub fn from_fn<T, G, F>(grid: &G, f: F) -> Self
+    where
+        T: Copy,
+        G: Grid<T> + ?Sized,
+        F: Fn(Point) -> bool,
+    {
+        let Rect { width, height, .. } = grid.bounds();
+        let mut cells = WrappedGrid::new(width, height, false);
+        for p in cells.bounds().points() {
+            cells.set(p, f(p));
+        }
+        Self::painted(cells)
+    }
+    //Rects can hang off the edge and wrap round
+    pub fn rect<T: Copy, G: Grid<T> + ?Sized>(grid: &G, rect: Rect) -> Self {
+        let Rect { width, height, .. } = grid.bounds();
+        Self::from_fn(grid, |p| {
+            let Point(x, y) = rect.local(p).wrap(width, height);
+            x < rect.width && y < rect.height
+        })
+    }
+    //Everything within radius of center, measured across the grid's topology
+    pub fn circle<T: Copy, G: Grid<T> + ?Sized>(
+        grid: &G,
+        center: Point,
+        radius: f64,
+        metric: Metric,
+    ) -> Self {
+        Self::from_fn(grid, |p| grid.metric_distance(center, p, metric) <= radius)
+    }
+    //Everything at least inner and less than outer from center
+    pub fn ring<T: Copy, G: Grid<T> + ?Sized>(
+        grid: &G,
+        center: Point,
+        inner: f64,
+        outer: f64,
+        metric: Metric,
+    ) -> Self {
+        Self::from_fn(grid, |p| {
+            let distance = grid.metric_distance(center, p, metric);
+            inner <= distance && distance < outer
+        })
+    }
+    //The frame of the rect thickness cells deep, all the way round its edges
+    pub fn band<T: Copy, G: Grid<T> + ?Sized>(grid: &G, rect: Rect, thickness: usize) -> Self {
+        let inside = Rect::new(
+            rect.origin + Offset(thickness as isize, thickness as isize),
+            rect.width.saturating_sub(2 * thickness),
+            rect.height.saturating_sub(2 * thickness),
+        );
+        Self::rect(grid, rect).difference(&Self::rect(grid, inside))
+    }
+    //Exactly the cells that are true
+    pub fn painted(cells: WrappedGrid<bool>) -> Self {
+        let members = cells
+            .iter()
+            .filter(|(_, inside)| *inside)
+            .map(|(p, _)| p)
+            .collect();
+        Zone {
+            cells,
+            members,
+            color: color::WHITE,
+        }
+    }
+    //A PNG where white is in the zone and black is out of it
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, String> {
+        let palette = [(color::WHITE, true), (color::BLACK, false)];
+        Ok(Self::painted(image::load(path, &palette)?))
+    }
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.get(p)
+    }
+    pub fn points(&self) -> &[Point] {
+        &self.members
+    }
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+    pub fn sample(&self) -> Option<Point> {
+        match self.members.len() {
+            0 => None,
+            n => Some(self.members[roll::usize(n)]),
+        }
+    }
+    //A uniformly chosen member that f also picks, e.g. one that isn't a wall
+    pub fn sample_where<F: Fn(Point) -> bool>(&self, f: F) -> Option<Point> {
+        for _ in 0..REJECTION_TRIES {
+            match self.sample() {
+                Some(p) if f(p) => return Some(p),
+                Some(_) => (),
+                None => return None,
+            }
+        }
+        let found: Vec<Point> = self.members.iter().copied().filter(|p| f(*p)).collect();
+        match found.len() {
+            0 => None,
+            n => Some(found[roll::usize(n)]),
+        }
+    }
+    pub fn union(&self, other: &Zone) -> Self {
+        self.combine(other, |a, b| a || b)
+    }
+    pub fn intersection(&self, other: &Zone) -> Self {
+        self.combine(other, |a, b| a && b)
+    }
+    pub fn difference(&self, other: &Zone) -> Self {
+        self.combine(other, |a, b| a && !b)
+    }
+    fn combine<F: Fn(bool, bool) -> bool>(&self, other: &Zone, f: F) -> Self {
+        let mut cells = self.cells.clone();
+        for (p, inside) in self.cells.iter() {
+            cells.set(p, f(inside, other.contains(p)));
+        }
+        Self::painted(cells).with_color(self.color)
+    }
+}
+
+//The zones of one world, by name
+#[derive(Clone, Default)]
+pub struct Zones {
+    zones: Vec<(String, Zone)>,
+}
+
+impl Zones {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    //Replaces any zone already going by that name
+    pub fn with(mut self, name: &str, zone: Zone) -> Self {
+        self.add(name, zone);
+        self
+    }
+    pub fn add(&mut self, name: &str, zone: Zone) {
+        match self.zones.iter_mut().find(|(n, _)| n == name) {
+            Some((_, existing)) => *existing = zone,
+            None => self.zones.push((name.to_string(), zone)),
+        }
+    }
+    pub fn get(&self, name: &str) -> Option<&Zone> {
+        self.zones
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, zone)| zone)
+    }
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.zones.iter().map(|(name, _)| name.as_str())
+    }
+    //Every zone p is in
+    pub fn at(&self, p: Point) -> impl Iterator<Item = &str> {
+        self.zones
+            .iter()
+            .filter(move |(_, zone)| zone.contains(p))
+            .map(|(name, _)| name.as_str())
+    }
+}
+
+impl Index<&str> for Zones {
+    type Output = Zone;
+    fn index(&self, name: &str) -> &Zone {
+        self.get(name)
+            .unwrap_or_else(|| panic!("There's no zone called {name}"))
+    }
+}
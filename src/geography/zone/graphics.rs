@@ -0,0 +1,25 @@
+use super::*;
+use crate::utils::draw_utils::Draw;
+use cairo::Context;
+
+//A faint wash of each zone's color over whatever's already drawn
+impl Draw for Zones {
+    fn draw(&self, context: &Context) {
+        for (_, zone) in self.zones.iter() {
+            let Rect { width, height, .. } = zone.cells.bounds();
+            let size = usize::max(width, height);
+            let square = color::get_scale(size);
+            let Color { r, g, b } = zone.color;
+            context.set_source_rgba(r, g, b, 0.25);
+            for Point(x, y) in zone.members.iter() {
+                context.rectangle(
+                    color::scale(*x, size),
+                    color::scale(*y, size),
+                    square,
+                    square,
+                );
+            }
+            context.fill().unwrap();
+        }
+    }
+}
@@ -1,5 +1,6 @@
 #![allow(unused_labels)]
 
+pub mod bench;
 pub mod burgs;
 pub mod constants;
 pub mod genes;
@@ -7,6 +8,7 @@ pub mod geography;
 pub mod maze;
 mod run;
 pub mod simulation;
+mod sync;
 pub mod utils;
 
 fn main() {
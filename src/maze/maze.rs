@@ -1,5 +1,5 @@
 use super::*;
-use crate::geography::{wrapped::*, Cardinal::*, *};
+use crate::geography::{Cardinal::*, *};
 use crate::utils::*;
 
 #[derive(Debug)]
@@ -1,4 +1,6 @@
+use crate::bench::{self, BenchArgs};
 use crate::burgs::*;
+use crate::geography::*;
 use crate::simulation::*;
 
 use gtk::prelude::*;
@@ -9,19 +11,32 @@ use std::time;
 
 use clap::*;
 
-#[derive(Parser, Debug, Copy, Clone)]
+#[derive(Parser, Debug, Clone)]
 struct Cli {
     #[command(subcommand)]
     simulation: Simulation,
 }
 
-#[derive(Subcommand, Debug, Copy, Clone)]
+#[derive(Subcommand, Debug, Clone)]
 enum Simulation {
     Unburg(UnburgArgs),
     Foodburg(FoodburgArgs),
     Mazeburg(MazeburgArgs),
     Scentburg(ScentburgArgs),
     Simpleburg(SimpleArgs),
+    Bench(BenchArgs),
+}
+
+//Each backing grid makes a different burg type, so the choice has to be matched out here
+macro_rules! with_grid {
+    ($burg:ident, $args:expr) => {
+        match $args.grid {
+            GridKind::Wrapped => run_helper($burg::<LockedGrid<WrappedGrid<_>>>::new($args)),
+            GridKind::Rw => run_helper($burg::<RwGrid<_>>::new($args)),
+            GridKind::Regional => run_helper($burg::<RegionalGrid<_>>::new($args)),
+            GridKind::Atomic => run_helper($burg::<AtomicGrid<_>>::new($args)),
+        }
+    };
 }
 
 pub fn run() {
@@ -33,21 +48,18 @@ pub fn run() {
             run_helper(sim);
         }
         Simulation::Foodburg(args) => {
-            let sim = Foodburg::new(args);
-            run_helper(sim)
+            with_grid!(Foodburg, args)
         }
         Simulation::Simpleburg(args) => {
-            let sim = Simpleburg::new(args);
-            run_helper(sim)
+            with_grid!(Simpleburg, args)
         }
         Simulation::Mazeburg(args) => {
-            let sim = Mazeburg::new(args);
-            run_helper(sim)
+            with_grid!(Mazeburg, args)
         }
         Simulation::Scentburg(args) => {
-            let sim = Scentburg::new(args);
-            run_helper(sim)
+            with_grid!(Scentburg, args)
         }
+        Simulation::Bench(args) => bench::run(args),
     }
 }
 fn run_helper<T: Petersburg>(simulation: T) {
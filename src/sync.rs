@@ -0,0 +1,12 @@
+//Everything the region-locked grids lock and count with, so loom can stand in for std when model checking them.
+//See geography/grid/loom_tests.rs for how to run it.
+#[cfg(loom)]
+pub(crate) use loom::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
+#[cfg(not(loom))]
+pub(crate) use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    RwLock, RwLockReadGuard, RwLockWriteGuard,
+};
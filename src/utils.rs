@@ -1,6 +1,6 @@
 pub mod color;
 pub mod draw_utils;
 pub mod public;
-pub use self::public::*; //Special pattern-breaking - this lets us bring in very commonly used things by direct reference.
 pub use self::color::types::*;
 pub use self::draw_utils::types::*;
+pub use self::public::*; //Special pattern-breaking - this lets us bring in very commonly used things by direct reference.
@@ -28,3 +28,66 @@ pub fn path_helper(context: &Context, size: usize, path_color: Color, path: &Vec
     }
     context.stroke().unwrap();
 }
+
+//Fits the grid's bounds to the window, drawing everything that isn't black
+pub fn grid_helper<T, G>(context: &Context, grid: &G)
+where
+    T: Colored + Copy,
+    G: Grid<T> + ?Sized,
+{
+    rects_helper(context, grid, &[grid.bounds()]);
+}
+
+//Like grid_helper, but a region at a time, so a frame never holds up workers outside the region being drawn
+pub fn regions_helper<T, G>(context: &Context, grid: &G)
+where
+    T: Colored + Copy,
+    G: ThreadedGrid<T> + ?Sized,
+{
+    rects_helper(context, grid, &grid.regions());
+}
+
+fn rects_helper<T, G>(context: &Context, grid: &G, rects: &[Rect])
+where
+    T: Colored + Copy,
+    G: Grid<T> + ?Sized,
+{
+    let bounds = grid.bounds();
+    let size = usize::max(bounds.width, bounds.height);
+    let square = color::get_scale(size);
+    for rect in rects {
+        for (p, value) in grid.iter_rect(*rect) {
+            let color = value.color();
+            if color != color::BLACK {
+                let Point(x, y) = bounds.local(p);
+                context.set_color(color);
+                context.rectangle(color::scale(x, size), color::scale(y, size), square, square);
+                context.fill().unwrap();
+            }
+        }
+    }
+}
+
+//Tints each region red by how long workers spent waiting on it, relative to the worst one
+pub fn heatmap_helper(context: &Context, size: usize, stats: &[RegionStats]) {
+    let worst = match stats.iter().map(|s| s.wait).max() {
+        Some(worst) if !worst.is_zero() => worst.as_secs_f64(),
+        _ => return,
+    };
+    for s in stats {
+        let heat = s.wait.as_secs_f64() / worst;
+        let Rect {
+            origin,
+            width,
+            height,
+        } = s.rect;
+        context.set_source_rgba(1.0, 0.0, 0.0, 0.6 * heat);
+        context.rectangle(
+            color::scale(origin.0, size),
+            color::scale(origin.1, size),
+            color::scale(width, size),
+            color::scale(height, size),
+        );
+        context.fill().unwrap();
+    }
+}